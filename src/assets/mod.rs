@@ -0,0 +1,300 @@
+//! Every texture the game draws goes through the [`AssetManager`]: code keeps
+//! a [`TextureHandle`], the manager keeps the texture behind it. Files load on
+//! first use, come from the active resource packs before `assets/`, and a
+//! missing or broken one is reported once and drawn as the placeholder
+//! [`PLACEHOLDER_TEXTURE`] instead.
+//! In dev mode the manager polls the files it loaded and swaps in edits.
+
+use macroquad::prelude::*;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use crate::packs;
+
+/// Drawn in place of any texture that cannot be loaded.
+pub const PLACEHOLDER_TEXTURE: &str = "assets/textures/tiles/empty.png";
+
+/// Seconds between two checks for changed files in dev mode.
+pub const POLL_INTERVAL: f64 = 1.0;
+
+/// Refers to one texture in the [`AssetManager`]. Handles are cheap to copy
+/// and stay valid when the texture behind them reloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureHandle(usize);
+
+/// A texture that could not be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetError {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// The file a texture was read from and when it last changed.
+type Source = (PathBuf, Option<SystemTime>);
+
+struct TextureSlot {
+    path: String,
+    /// `None` until first use or after a reload was requested.
+    texture: Option<Texture2D>,
+    source: Option<Source>,
+    error: Option<String>,
+}
+
+/// The file `path` currently resolves to: the first resource pack that has
+/// it, else the base file, if that exists.
+fn current_source(path: &str) -> Option<Source> {
+    packs::candidates(path).into_iter().find(|file| file.is_file()).map(|file| {
+        let modified = std::fs::metadata(&file).and_then(|m| m.modified()).ok();
+        (file, modified)
+    })
+}
+
+/// Reads and decodes the first of `path`'s candidate files that works.
+fn decode(path: &str) -> Result<(image::RgbaImage, Source), String> {
+    let mut errors = Vec::new();
+    for file in packs::candidates(path) {
+        let modified = std::fs::metadata(&file).and_then(|m| m.modified()).ok();
+        match image::open(&file) {
+            Ok(image) => return Ok((image.to_rgba8(), (file, modified))),
+            Err(e) => errors.push(format!("{}: {}", file.display(), e)),
+        }
+    }
+    Err(errors.join("; "))
+}
+
+fn upload(image: &image::RgbaImage) -> Texture2D {
+    let texture = Texture2D::from_rgba8(image.width() as u16, image.height() as u16, image);
+    texture.set_filter(FilterMode::Nearest);
+    texture
+}
+
+/// The placeholder texture, or magenta and black squares if even that is
+/// missing, so missing textures stand out instead of vanishing.
+fn fallback_texture() -> Texture2D {
+    match decode(PLACEHOLDER_TEXTURE) {
+        Ok((image, _)) => upload(&image),
+        Err(message) => {
+            eprintln!("Failed to load the placeholder texture: {}", message);
+            const M: [u8; 4] = [255, 0, 255, 255];
+            const B: [u8; 4] = [0, 0, 0, 255];
+            let texture = Texture2D::from_rgba8(2, 2, &[M, B, B, M].concat());
+            texture.set_filter(FilterMode::Nearest);
+            texture
+        }
+    }
+}
+
+pub struct AssetManager {
+    slots: Vec<TextureSlot>,
+    handles: HashMap<String, TextureHandle>,
+    fallback: Option<Texture2D>,
+    hot_reload: bool,
+    last_poll: f64,
+}
+
+impl Default for AssetManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AssetManager {
+    pub fn new() -> Self {
+        Self { slots: Vec::new(), handles: HashMap::new(), fallback: None, hot_reload: false, last_poll: 0.0 }
+    }
+
+    /// The handle for the texture at `path`, relative to the working
+    /// directory. Nothing is loaded yet, so this works without a window.
+    pub fn texture(&mut self, path: &str) -> TextureHandle {
+        if let Some(handle) = self.handles.get(path) {
+            return *handle;
+        }
+        let handle = TextureHandle(self.slots.len());
+        self.slots.push(TextureSlot { path: path.to_string(), texture: None, source: None, error: None });
+        self.handles.insert(path.to_string(), handle);
+        handle
+    }
+
+    pub fn path(&self, handle: TextureHandle) -> &str {
+        &self.slots[handle.0].path
+    }
+
+    fn fallback(&mut self) -> Texture2D {
+        self.fallback.get_or_insert_with(fallback_texture).clone()
+    }
+
+    fn load(&mut self, index: usize) -> Texture2D {
+        let texture = match decode(&self.slots[index].path) {
+            Ok((image, source)) => {
+                let texture = upload(&image);
+                let slot = &mut self.slots[index];
+                slot.source = Some(source);
+                slot.error = None;
+                texture
+            }
+            Err(message) => {
+                eprintln!("Failed to load texture {}: {}; drawing the fallback", self.slots[index].path, message);
+                let slot = &mut self.slots[index];
+                slot.source = current_source(&slot.path);
+                slot.error = Some(message);
+                self.fallback()
+            }
+        };
+        self.slots[index].texture = Some(texture.clone());
+        texture
+    }
+
+    /// The texture behind `handle`, loading it on first use.
+    pub fn get(&mut self, handle: TextureHandle) -> Texture2D {
+        match &self.slots[handle.0].texture {
+            Some(texture) => texture.clone(),
+            None => self.load(handle.0),
+        }
+    }
+
+    /// Loads every texture that has a handle but was not used yet, and
+    /// returns everything that failed so far.
+    pub fn load_all(&mut self) -> Vec<AssetError> {
+        for index in 0..self.slots.len() {
+            if self.slots[index].texture.is_none() {
+                self.load(index);
+            }
+        }
+        self.errors()
+    }
+
+    pub fn errors(&self) -> Vec<AssetError> {
+        self.slots
+            .iter()
+            .filter_map(|slot| {
+                let message = slot.error.clone()?;
+                Some(AssetError { path: slot.path.clone(), message })
+            })
+            .collect()
+    }
+
+    /// Textures loaded so far and how many of them fell back.
+    pub fn stats(&self) -> (usize, usize) {
+        let loaded = self.slots.iter().filter(|slot| slot.texture.is_some()).count();
+        let failed = self.slots.iter().filter(|slot| slot.error.is_some()).count();
+        (loaded, failed)
+    }
+
+    /// Makes every texture load again on its next use.
+    pub fn reload_all(&mut self) {
+        for slot in &mut self.slots {
+            slot.texture = None;
+        }
+    }
+
+    pub fn set_hot_reload(&mut self, enabled: bool) {
+        self.hot_reload = enabled;
+    }
+
+    /// Indices of loaded textures whose file changed, appeared or moved to
+    /// another pack since they were read.
+    fn changed(&self) -> Vec<usize> {
+        (0..self.slots.len())
+            .filter(|&index| {
+                let slot = &self.slots[index];
+                slot.texture.is_some() && current_source(&slot.path) != slot.source
+            })
+            .collect()
+    }
+
+    /// In dev mode, reloads changed textures at most every [`POLL_INTERVAL`]
+    /// seconds. Returns how many were reloaded.
+    pub fn poll(&mut self, now: f64) -> usize {
+        if !self.hot_reload || now - self.last_poll < POLL_INTERVAL {
+            return 0;
+        }
+        self.last_poll = now;
+        let changed = self.changed();
+        for &index in &changed {
+            self.load(index);
+        }
+        changed.len()
+    }
+}
+
+static MANAGER: Lazy<Mutex<AssetManager>> = Lazy::new(|| Mutex::new(AssetManager::new()));
+
+/// The game's asset manager.
+pub fn manager() -> MutexGuard<'static, AssetManager> {
+    MANAGER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Shorthand for [`AssetManager::texture`] on the game's manager.
+pub fn texture(path: &str) -> TextureHandle {
+    manager().texture(path)
+}
+
+/// Shorthand for [`AssetManager::get`] on the game's manager.
+pub fn get(handle: TextureHandle) -> Texture2D {
+    manager().get(handle)
+}
+
+/// Loads every known texture and prints what failed, once, in one place.
+pub fn load_all_and_report() {
+    let errors = manager().load_all();
+    if !errors.is_empty() {
+        let lines: Vec<String> = errors.iter().map(AssetError::to_string).collect();
+        eprintln!("{} textures are missing or broken:\n  {}", errors.len(), lines.join("\n  "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn handles_are_shared_per_path() {
+        let mut assets = AssetManager::new();
+        let grass = assets.texture("assets/textures/tiles/grass.png");
+        let sand = assets.texture("assets/textures/tiles/sand.png");
+        assert_ne!(grass, sand);
+        assert_eq!(assets.texture("assets/textures/tiles/grass.png"), grass);
+        assert_eq!(assets.path(sand), "assets/textures/tiles/sand.png");
+        assert_eq!(assets.stats(), (0, 0));
+    }
+
+    #[test]
+    fn decodes_bundled_textures_and_reports_broken_ones() {
+        let (image, (file, _)) = decode("assets/textures/tiles/grass.png").unwrap();
+        assert!(image.width() > 0);
+        assert_eq!(file, PathBuf::from("assets/textures/tiles/grass.png"));
+        assert!(decode(PLACEHOLDER_TEXTURE).is_ok());
+        assert!(decode("assets/textures/tiles/nope.png").is_err());
+        let temp = TempDir::new().unwrap();
+        let broken = temp.path().join("broken.png");
+        std::fs::write(&broken, b"not an image").unwrap();
+        assert!(decode(&broken.to_string_lossy()).unwrap_err().contains("broken.png"));
+    }
+
+    #[test]
+    fn notices_edited_and_new_files() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("edited.png");
+        let path_str = path.to_string_lossy().into_owned();
+        assert_eq!(current_source(&path_str), None);
+        std::fs::copy("assets/textures/tiles/grass.png", &path).unwrap();
+        let before = current_source(&path_str).unwrap();
+        assert_eq!(before.0, path);
+        let later = SystemTime::now() + Duration::from_secs(10);
+        File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        assert_ne!(current_source(&path_str), Some(before));
+    }
+}
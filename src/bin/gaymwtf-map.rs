@@ -0,0 +1,72 @@
+//! Renders a rectangle of chunks of a seed to a PNG, without a window or GPU.
+
+use anyhow::{bail, Context};
+use gaymwtf_bin::cli::{Args, GENERATOR_OPTIONS, GENERATOR_USAGE};
+use gaymwtf_bin::content::Content;
+use gaymwtf_bin::worldgen::preview::render_chunk_map;
+use gaymwtf_bin::worldgen::WorldGenerator;
+use gaymwtf_core::CHUNK_SIZE;
+use std::process::ExitCode;
+
+const DEFAULT_CHUNKS: [i32; 4] = [-8, -8, 7, 7];
+/// Largest image side, in pixels, the tool will render.
+const MAX_IMAGE_SIDE: u32 = 16384;
+
+fn print_usage() {
+    println!("Usage: gaymwtf-map --seed <seed> [options]");
+    println!();
+    println!("  --seed <seed>             world seed, a number or any text");
+    println!("  --chunks <x0,y0,x1,y1>    chunk rectangle, corners inclusive (default: -8,-8,7,7)");
+    println!("  --tile-pixels <n>         pixels per tile side (default: 1)");
+    println!("  --objects                 draw trees and other objects on top of the biomes");
+    println!("  --output <path>           PNG to write (default: map.png)");
+    println!("{}", GENERATOR_USAGE);
+}
+
+fn run(args: &Args) -> anyhow::Result<()> {
+    let seed = args.seed("seed")?;
+    let settings = args.generator_settings()?;
+    let [x0, y0, x1, y1] = args.get_list::<i32, 4>("chunks")?.unwrap_or(DEFAULT_CHUNKS);
+    let tile_pixels: u32 = args.get_or("tile-pixels", 1)?;
+    let output = args.text("output").unwrap_or("map.png");
+
+    if tile_pixels == 0 {
+        bail!("--tile-pixels must be at least 1");
+    }
+    let side = |a: i32, b: i32| (a.abs_diff(b) as u64 + 1) * CHUNK_SIZE as u64 * tile_pixels as u64;
+    let (width, height) = (side(x0, x1), side(y0, y1));
+    if width.max(height) > MAX_IMAGE_SIDE as u64 {
+        bail!("The map would be {}x{} pixels; the limit is {} per side", width, height, MAX_IMAGE_SIDE);
+    }
+
+    let generator = WorldGenerator::from_settings(seed, &settings);
+    let biome_registry = gaymwtf_bin::init_biome_registry(&Content::base()?)?;
+    let image = render_chunk_map(&generator, &biome_registry, (x0, y0), (x1, y1), tile_pixels, args.switch("objects"));
+    image.save(output).with_context(|| format!("Failed to write {}", output))?;
+    println!("Seed {}: wrote {}x{} map to {}", seed, width, height, output);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut options = vec!["seed", "chunks", "tile-pixels", "output"];
+    options.extend(GENERATOR_OPTIONS);
+    let args = match Args::parse(std::env::args().skip(1), &options, &["objects", "help"]) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            print_usage();
+            return ExitCode::from(2);
+        }
+    };
+    if args.switch("help") {
+        print_usage();
+        return ExitCode::SUCCESS;
+    }
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Scans a range of seeds on every core and prints those that meet the given criteria.
+
+use anyhow::bail;
+use gaymwtf_bin::cli::{Args, GENERATOR_OPTIONS, GENERATOR_USAGE};
+use gaymwtf_bin::content::Content;
+use gaymwtf_bin::worldgen::finder::{check_seed, Criterion, SeedMatch, MAX_RADIUS};
+use rayon::prelude::*;
+use std::process::ExitCode;
+use std::time::Instant;
+
+const DEFAULT_COUNT: u64 = 1000;
+const DEFAULT_RADIUS: i32 = 4;
+/// Seeds checked between progress reports and limit checks.
+const BATCH_SIZE: u64 = 256;
+
+fn print_usage() {
+    println!("Usage: gaymwtf-seeds [options]");
+    println!();
+    println!("  --from <seed>             first numeric seed to check (default: 0)");
+    println!("  --count <n>               how many seeds to check (default: 1000)");
+    println!("  --near <biome,...>        biomes that must all be near the spawn; a prefix such");
+    println!("                            as 'snow' matches snow_plains and snow_forest");
+    println!("  --radius <chunks>         how far from the spawn chunk to look (default: 4, at most {})", MAX_RADIUS);
+    println!("  --river <tiles>           require a river at least this long near the spawn");
+    println!("  --spawn-on-land           reject seeds whose spawn is on water");
+    println!("  --limit <n>               stop after the first n matching seeds");
+    println!("  --threads <n>             worker threads (default: one per core)");
+    println!("{}", GENERATOR_USAGE);
+}
+
+fn criteria(args: &Args) -> anyhow::Result<Vec<Criterion>> {
+    let radius: i32 = args.get_or("radius", DEFAULT_RADIUS)?;
+    if radius < 0 {
+        bail!("--radius cannot be negative");
+    }
+    if radius > MAX_RADIUS {
+        bail!("--radius {} is too large; the limit is {} chunks", radius, MAX_RADIUS);
+    }
+    let mut criteria = Vec::new();
+    if args.switch("spawn-on-land") {
+        criteria.push(Criterion::SpawnOnLand);
+    }
+    if let Some(min_length) = args.get("river")? {
+        criteria.push(Criterion::RiverLength { min_length, radius });
+    }
+    for name in args.text("near").unwrap_or("").split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        criteria.push(Criterion::BiomeNearSpawn { name: name.to_string(), radius });
+    }
+    if criteria.is_empty() {
+        bail!("Give at least one of --near, --river or --spawn-on-land");
+    }
+    Ok(criteria)
+}
+
+fn print_match(found: &SeedMatch) {
+    let river = found.river_length.map_or(String::new(), |length| format!("  river {} tiles", length));
+    let spawn = format!("{},{}", found.spawn.0, found.spawn.1);
+    println!("{:>20}  spawn {:<13} {}{}", found.seed, spawn, found.spawn_biome, river);
+}
+
+fn run(args: &Args) -> anyhow::Result<()> {
+    let criteria = criteria(args)?;
+    let settings = args.generator_settings()?;
+    let from: u64 = args.get_or("from", 0)?;
+    let count: u64 = args.get_or("count", DEFAULT_COUNT)?;
+    let limit: Option<usize> = args.get("limit")?;
+    if let Some(threads) = args.get::<usize>("threads")? {
+        rayon::ThreadPoolBuilder::new().num_threads(threads).build_global()?;
+    }
+    let content = Content::base()?;
+    let biome_registry = gaymwtf_bin::init_biome_registry(&content)?;
+    let end = from.saturating_add(count);
+
+    let started = Instant::now();
+    let mut matches: Vec<SeedMatch> = Vec::new();
+    let mut batch_start = from;
+    while batch_start < end && limit.is_none_or(|limit| matches.len() < limit) {
+        let batch_end = batch_start.saturating_add(BATCH_SIZE).min(end);
+        let mut found: Vec<SeedMatch> = (batch_start..batch_end)
+            .into_par_iter()
+            .filter_map(|seed| check_seed(seed, &settings, &biome_registry, &content.manifest, &criteria))
+            .collect();
+        found.sort_by_key(|m| m.seed);
+        if let Some(limit) = limit {
+            found.truncate(limit - matches.len());
+        }
+        for m in &found {
+            print_match(m);
+        }
+        matches.extend(found);
+        eprintln!("Checked {}/{} seeds, {} matching", batch_end - from, count, matches.len());
+        batch_start = batch_end;
+    }
+    eprintln!(
+        "Found {} matching seeds in {:.1}s",
+        matches.len(),
+        started.elapsed().as_secs_f32()
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut options = vec!["from", "count", "near", "radius", "river", "limit", "threads"];
+    options.extend(GENERATOR_OPTIONS);
+    let args = match Args::parse(std::env::args().skip(1), &options, &["spawn-on-land", "help"]) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            print_usage();
+            return ExitCode::from(2);
+        }
+    };
+    if args.switch("help") {
+        print_usage();
+        return ExitCode::SUCCESS;
+    }
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
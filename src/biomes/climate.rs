@@ -0,0 +1,300 @@
+use gaymwtf_core::Biome;
+use serde::Deserialize;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Inclusive ranges of the three noise values a biome accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClimateRange {
+    pub height: (f64, f64),
+    pub moisture: (f64, f64),
+    pub temperature: (f64, f64),
+}
+
+impl ClimateRange {
+    pub fn contains(&self, height: f64, moisture: f64, temperature: f64) -> bool {
+        let within = |(min, max): (f64, f64), value: f64| (min..=max).contains(&value);
+        within(self.height, height) && within(self.moisture, moisture) && within(self.temperature, temperature)
+    }
+
+    /// The middle of the ranges, where the biome fits best.
+    pub fn ideal(&self) -> (f64, f64, f64) {
+        let mid = |(min, max): (f64, f64)| (min + max) / 2.0;
+        (mid(self.height), mid(self.moisture), mid(self.temperature))
+    }
+
+    /// Distance from the ideal climate, with each axis scaled by half its
+    /// range so that the edge of any range is 1 away from its middle.
+    pub fn distance(&self, height: f64, moisture: f64, temperature: f64) -> f64 {
+        let axis = |(min, max): (f64, f64), value: f64| {
+            let half = ((max - min) / 2.0).max(f64::EPSILON);
+            (value - (min + max) / 2.0) / half
+        };
+        let (h, m, t) = (
+            axis(self.height, height),
+            axis(self.moisture, moisture),
+            axis(self.temperature, temperature),
+        );
+        (h * h + m * m + t * t).sqrt()
+    }
+}
+
+/// A biome described by its climate ranges instead of an opaque check, so
+/// overlapping biomes can be ranked against each other.
+pub trait ClimateBiome: Biome {
+    fn climate(&self) -> ClimateRange;
+
+    /// Biomes with a higher priority always win where ranges overlap.
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// Among equal priorities, a heavier biome claims more of an overlap.
+    fn weight(&self) -> f64 {
+        1.0
+    }
+}
+
+/// How well `biome` fits a climate; lower is better.
+fn score(biome: &dyn ClimateBiome, height: f64, moisture: f64, temperature: f64) -> f64 {
+    biome.climate().distance(height, moisture, temperature) / biome.weight().max(f64::EPSILON)
+}
+
+/// Index of the biome that claims a climate: the highest priority among the
+/// biomes that accept it, then the best score, then the first tag in
+/// alphabetical order. Registration order never matters.
+pub fn best_fit(biomes: &[Box<dyn ClimateBiome>], height: f64, moisture: f64, temperature: f64) -> Option<usize> {
+    biomes
+        .iter()
+        .enumerate()
+        .filter(|(_, biome)| biome.is_suitable(height, moisture, temperature))
+        .min_by(|(_, a), (_, b)| {
+            b.priority()
+                .cmp(&a.priority())
+                .then(score(a.as_ref(), height, moisture, temperature).total_cmp(&score(b.as_ref(), height, moisture, temperature)))
+                .then(a.get_type_tag().cmp(b.get_type_tag()))
+        })
+        .map(|(index, _)| index)
+}
+
+/// The biomes ranked together, with an id no other set shares.
+struct RankedSet {
+    id: u64,
+    biomes: Vec<Box<dyn ClimateBiome>>,
+}
+
+static NEXT_SET_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A resolved [`best_fit`]: set id, the climate's bits and the winner.
+type Fit = (u64, [u64; 3], Option<usize>);
+
+thread_local! {
+    /// The last fit this thread resolved.
+    static LAST_FIT: Cell<Option<Fit>> = const { Cell::new(None) };
+}
+
+impl RankedSet {
+    /// [`best_fit`] of the set. `find_biome` asks every entry in turn about
+    /// the same climate, so the winner is resolved once and then reused.
+    fn winner(&self, height: f64, moisture: f64, temperature: f64) -> Option<usize> {
+        let key = [height.to_bits(), moisture.to_bits(), temperature.to_bits()];
+        if let Some((id, last, winner)) = LAST_FIT.get() {
+            if id == self.id && last == key {
+                return winner;
+            }
+        }
+        let winner = best_fit(&self.biomes, height, moisture, temperature);
+        LAST_FIT.set(Some((self.id, key, winner)));
+        winner
+    }
+}
+
+/// One biome of a ranked set, as registered in a `BiomeRegistry`.
+///
+/// `BiomeRegistry::find_biome` returns the first biome whose `is_suitable`
+/// is true. A ranked biome is only suitable where it is the [`best_fit`] of
+/// its whole set, so the registry returns the best fit no matter the order
+/// the set was registered in.
+#[derive(Clone)]
+pub struct RankedBiome {
+    set: Arc<RankedSet>,
+    index: usize,
+}
+
+impl RankedBiome {
+    /// One entry per biome in `biomes`, ready to register.
+    pub fn rank(biomes: Vec<Box<dyn ClimateBiome>>) -> Vec<RankedBiome> {
+        let set = Arc::new(RankedSet { id: NEXT_SET_ID.fetch_add(1, Ordering::Relaxed), biomes });
+        (0..set.biomes.len()).map(|index| RankedBiome { set: set.clone(), index }).collect()
+    }
+
+    fn biome(&self) -> &dyn ClimateBiome {
+        self.set.biomes[self.index].as_ref()
+    }
+}
+
+impl Biome for RankedBiome {
+    fn get_type_tag(&self) -> &'static str {
+        self.biome().get_type_tag()
+    }
+
+    fn is_suitable(&self, height: f64, moisture: f64, temperature: f64) -> bool {
+        self.biome().is_suitable(height, moisture, temperature)
+            && self.set.winner(height, moisture, temperature) == Some(self.index)
+    }
+
+    fn get_ground_tile_type(&self) -> &'static str {
+        self.biome().get_ground_tile_type()
+    }
+
+    fn get_spawnable_objects(&self) -> Vec<(&'static str, f32)> {
+        self.biome().get_spawnable_objects()
+    }
+
+    fn clone_box(&self) -> Box<dyn Biome> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gaymwtf_core::BiomeRegistry;
+    use crate::content::Content;
+
+    fn registry(biomes: Vec<Box<dyn ClimateBiome>>) -> BiomeRegistry {
+        let mut registry = BiomeRegistry::new();
+        for biome in RankedBiome::rank(biomes) {
+            registry.register(biome);
+        }
+        registry
+    }
+
+    #[test]
+    fn registration_order_does_not_change_the_result() {
+        let forward = registry(crate::biomes::all(&Content::base().unwrap()).unwrap());
+        let mut reversed = crate::biomes::all(&Content::base().unwrap()).unwrap();
+        reversed.reverse();
+        let reversed = registry(reversed);
+        let steps = [0.0, 0.3, 0.48, 0.5, 0.6, 0.7, 0.85, 1.0];
+        for height in steps {
+            for moisture in steps {
+                for temperature in steps {
+                    let tag = |r: &BiomeRegistry| r.find_biome(height, moisture, temperature).map(|b| b.get_type_tag());
+                    assert_eq!(tag(&forward), tag(&reversed), "at {} {} {}", height, moisture, temperature);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn priority_beats_fit() {
+        // Hot and dry land is both plains and desert; desert has the higher priority.
+        let registry = registry(crate::biomes::all(&Content::base().unwrap()).unwrap());
+        let biome = registry.find_biome(0.99, 0.29, 0.71).unwrap();
+        assert_eq!(biome.get_type_tag(), "desert");
+    }
+
+    #[derive(Clone)]
+    struct Test(&'static str, ClimateRange, f64);
+
+    impl Biome for Test {
+        fn get_type_tag(&self) -> &'static str {
+            self.0
+        }
+
+        fn is_suitable(&self, height: f64, moisture: f64, temperature: f64) -> bool {
+            self.climate().contains(height, moisture, temperature)
+        }
+
+        fn get_ground_tile_type(&self) -> &'static str {
+            "grass"
+        }
+
+        fn get_spawnable_objects(&self) -> Vec<(&'static str, f32)> {
+            vec![]
+        }
+
+        fn clone_box(&self) -> Box<dyn Biome> {
+            Box::new(self.clone())
+        }
+    }
+
+    impl ClimateBiome for Test {
+        fn climate(&self) -> ClimateRange {
+            self.1
+        }
+
+        fn weight(&self) -> f64 {
+            self.2
+        }
+    }
+
+    fn overlapping(weight: f64) -> Vec<Box<dyn ClimateBiome>> {
+        let range = |min, max| ClimateRange { height: (min, max), moisture: (0.0, 1.0), temperature: (0.0, 1.0) };
+        vec![Box::new(Test("low", range(0.0, 0.6), 1.0)), Box::new(Test("high", range(0.4, 1.0), weight))]
+    }
+
+    #[test]
+    fn closer_ideal_wins_between_equal_priorities() {
+        let biomes = overlapping(1.0);
+        assert_eq!(best_fit(&biomes, 0.45, 0.5, 0.5), Some(0));
+        assert_eq!(best_fit(&biomes, 0.55, 0.5, 0.5), Some(1));
+        // Exactly between both ideals the tags decide: "high" < "low".
+        assert_eq!(best_fit(&biomes, 0.5, 0.5, 0.5), Some(1));
+    }
+
+    #[test]
+    fn weight_widens_a_biomes_share_of_an_overlap() {
+        let biomes = overlapping(2.0);
+        assert_eq!(best_fit(&biomes, 0.45, 0.5, 0.5), Some(1));
+    }
+
+    /// Climate checks made by [`Counted`] biomes.
+    static CHECKS: AtomicU64 = AtomicU64::new(0);
+
+    #[derive(Clone)]
+    struct Counted(&'static str);
+
+    impl Biome for Counted {
+        fn get_type_tag(&self) -> &'static str {
+            self.0
+        }
+
+        fn is_suitable(&self, _height: f64, _moisture: f64, _temperature: f64) -> bool {
+            CHECKS.fetch_add(1, Ordering::Relaxed);
+            true
+        }
+
+        fn get_ground_tile_type(&self) -> &'static str {
+            "grass"
+        }
+
+        fn get_spawnable_objects(&self) -> Vec<(&'static str, f32)> {
+            vec![]
+        }
+
+        fn clone_box(&self) -> Box<dyn Biome> {
+            Box::new(self.clone())
+        }
+    }
+
+    impl ClimateBiome for Counted {
+        fn climate(&self) -> ClimateRange {
+            ClimateRange { height: (0.0, 1.0), moisture: (0.0, 1.0), temperature: (0.0, 1.0) }
+        }
+    }
+
+    #[test]
+    fn each_lookup_ranks_the_set_once() {
+        // All biomes fit equally, so the first tag wins; it is registered last.
+        let tags = (0..20).rev().map(|i| crate::utils::intern::intern(&format!("b{:02}", i)));
+        let registry = registry(tags.map(|tag| Box::new(Counted(tag)) as Box<dyn ClimateBiome>).collect());
+        CHECKS.store(0, Ordering::Relaxed);
+        assert_eq!(registry.find_biome(0.5, 0.5, 0.5).map(|b| b.get_type_tag()), Some("b00"));
+        // Each entry checks itself, and one ranking checks all of them.
+        assert_eq!(CHECKS.load(Ordering::Relaxed), 40);
+    }
+}
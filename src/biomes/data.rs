@@ -0,0 +1,267 @@
+use anyhow::{bail, Context};
+use gaymwtf_core::Biome;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::climate::{ClimateBiome, ClimateRange};
+use super::fallback::FALLBACK_BIOME;
+use crate::mods;
+use crate::utils::intern::intern;
+
+/// Where the game looks for biome files, relative to the working directory.
+pub const BIOMES_DIR: &str = "assets/biomes";
+
+/// One `assets/biomes/*.json` file, for example:
+///
+/// ```json
+/// {
+///   "tag": "forest",
+///   "ground": "grass",
+///   "climate": { "height": [0.5, 1.0], "moisture": [0.5, 1.0], "temperature": [0.3, 1.0] },
+///   "objects": [{ "id": "tree", "chance": 0.1 }]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BiomeFile {
+    pub tag: String,
+    /// Tile id of the ground.
+    pub ground: String,
+    pub climate: ClimateRange,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    #[serde(default)]
+    pub objects: Vec<SpawnEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpawnEntry {
+    pub id: String,
+    /// Chance per tile, from 0 to 1.
+    pub chance: f32,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl BiomeFile {
+    /// Everything wrong with the file; empty when it can be loaded.
+    pub fn problems(&self, tile_ids: &[&str], object_ids: &[&str]) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.tag.is_empty() || !self.tag.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            problems.push(format!("tag '{}' must be lowercase letters, digits and '_'", self.tag));
+        }
+        if self.tag == FALLBACK_BIOME {
+            problems.push(format!("tag '{}' is reserved", FALLBACK_BIOME));
+        }
+        if !tile_ids.contains(&self.ground.as_str()) {
+            problems.push(format!("unknown ground tile '{}'", self.ground));
+        }
+        let ClimateRange { height, moisture, temperature } = self.climate;
+        for (name, (min, max)) in [("height", height), ("moisture", moisture), ("temperature", temperature)] {
+            if !(0.0..=1.0).contains(&min) || !(0.0..=1.0).contains(&max) || min > max {
+                problems.push(format!("climate.{} [{}, {}] must be an ascending range within 0..1", name, min, max));
+            }
+        }
+        if !(self.weight.is_finite() && self.weight > 0.0) {
+            problems.push(format!("weight {} must be above 0", self.weight));
+        }
+        for entry in &self.objects {
+            if !object_ids.contains(&entry.id.as_str()) {
+                problems.push(format!("unknown object '{}'", entry.id));
+            }
+            if !(0.0..=1.0).contains(&entry.chance) {
+                problems.push(format!("chance {} of '{}' must be within 0..1", entry.chance, entry.id));
+            }
+        }
+        problems
+    }
+}
+
+/// A biome read from a data file.
+#[derive(Debug, Clone)]
+pub struct DataBiome {
+    tag: &'static str,
+    ground: &'static str,
+    climate: ClimateRange,
+    priority: i32,
+    weight: f64,
+    objects: Vec<(&'static str, f32)>,
+}
+
+impl From<BiomeFile> for DataBiome {
+    fn from(file: BiomeFile) -> Self {
+        Self {
+            tag: intern(&file.tag),
+            ground: intern(&file.ground),
+            climate: file.climate,
+            priority: file.priority,
+            weight: file.weight,
+            objects: file.objects.iter().map(|e| (intern(&e.id), e.chance)).collect(),
+        }
+    }
+}
+
+impl Biome for DataBiome {
+    fn get_type_tag(&self) -> &'static str {
+        self.tag
+    }
+
+    fn is_suitable(&self, height: f64, moisture: f64, temperature: f64) -> bool {
+        self.climate.contains(height, moisture, temperature)
+    }
+
+    fn get_ground_tile_type(&self) -> &'static str {
+        self.ground
+    }
+
+    fn get_spawnable_objects(&self) -> Vec<(&'static str, f32)> {
+        self.objects.clone()
+    }
+
+    fn clone_box(&self) -> Box<dyn Biome> {
+        Box::new(self.clone())
+    }
+}
+
+impl ClimateBiome for DataBiome {
+    fn climate(&self) -> ClimateRange {
+        self.climate
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn weight(&self) -> f64 {
+        self.weight
+    }
+}
+
+/// Parses one biome file and checks it against the known tile and object
+/// ids. A biome from a mod gets the mod's `namespace` on its tag, and its
+/// references are resolved as [`mods::resolve_reference`] describes.
+pub fn parse_biome(text: &str, namespace: Option<&str>, tile_ids: &[&str], object_ids: &[&str]) -> anyhow::Result<DataBiome> {
+    let mut file: BiomeFile = serde_json::from_str(text)?;
+    file.ground = mods::resolve_reference(&file.ground, namespace, tile_ids);
+    for entry in &mut file.objects {
+        entry.id = mods::resolve_reference(&entry.id, namespace, object_ids);
+    }
+    let problems = file.problems(tile_ids, object_ids);
+    if !problems.is_empty() {
+        bail!("{}", problems.join("; "));
+    }
+    if let Some(namespace) = namespace {
+        file.tag = mods::qualify(namespace, &file.tag);
+    }
+    Ok(file.into())
+}
+
+/// Loads every `*.json` file in `dir`, sorted by file name, with tags in
+/// `namespace` for a mod's biomes. All broken files are reported together
+/// instead of stopping at the first.
+pub fn load_biomes(
+    dir: &Path,
+    namespace: Option<&str>,
+    tile_ids: &[&str],
+    object_ids: &[&str],
+) -> anyhow::Result<Vec<Box<dyn ClimateBiome>>> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read biome directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut biomes: Vec<Box<dyn ClimateBiome>> = Vec::new();
+    let mut errors = Vec::new();
+    let mut tags = HashSet::new();
+    for path in &paths {
+        let biome = std::fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| parse_biome(&text, namespace, tile_ids, object_ids));
+        match biome {
+            Ok(biome) if !tags.insert(biome.tag) => {
+                errors.push(format!("{}: tag '{}' is already used by another file", path.display(), biome.tag));
+            }
+            Ok(biome) => biomes.push(Box::new(biome)),
+            Err(e) => errors.push(format!("{}: {:#}", path.display(), e)),
+        }
+    }
+    if !errors.is_empty() {
+        bail!("Invalid biome files:\n  {}", errors.join("\n  "));
+    }
+    Ok(biomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::Content;
+
+    const TILES: [&str; 2] = ["grass", "water"];
+    const OBJECTS: [&str; 1] = ["tree"];
+
+    #[test]
+    fn parses_a_valid_biome() {
+        let text = r#"{
+            "tag": "swamp",
+            "ground": "water",
+            "climate": { "height": [0.4, 0.6], "moisture": [0.8, 1.0], "temperature": [0.3, 1.0] },
+            "priority": 1,
+            "objects": [{ "id": "tree", "chance": 0.02 }]
+        }"#;
+        let biome = parse_biome(text, None, &TILES, &OBJECTS).unwrap();
+        assert_eq!(biome.get_type_tag(), "swamp");
+        assert_eq!(biome.get_ground_tile_type(), "water");
+        assert_eq!(biome.priority(), 1);
+        assert_eq!(biome.weight(), 1.0);
+        assert_eq!(biome.get_spawnable_objects(), vec![("tree", 0.02)]);
+    }
+
+    #[test]
+    fn rejects_unknown_ids_and_bad_ranges() {
+        let text = r#"{
+            "tag": "jungle",
+            "ground": "mud",
+            "climate": { "height": [0.9, 0.5], "moisture": [0.0, 1.0], "temperature": [0.0, 1.0] },
+            "objects": [{ "id": "vine", "chance": 0.5 }]
+        }"#;
+        let error = format!("{:#}", parse_biome(text, None, &TILES, &OBJECTS).unwrap_err());
+        assert!(error.contains("unknown ground tile 'mud'"), "{}", error);
+        assert!(error.contains("climate.height"), "{}", error);
+        assert!(error.contains("unknown object 'vine'"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let text = r#"{ "tag": "x", "ground": "grass", "climat": {} }"#;
+        assert!(parse_biome(text, None, &TILES, &OBJECTS).is_err());
+    }
+
+    #[test]
+    fn mod_biomes_are_namespaced_and_prefer_their_own_ids() {
+        let text = r#"{
+            "tag": "jungle",
+            "ground": "grass",
+            "climate": { "height": [0.5, 1.0], "moisture": [0.8, 1.0], "temperature": [0.7, 1.0] },
+            "objects": [{ "id": "tree", "chance": 0.2 }]
+        }"#;
+        let objects = ["tree", "tropics:tree"];
+        let biome = parse_biome(text, Some("tropics"), &TILES, &objects).unwrap();
+        assert_eq!(biome.get_type_tag(), "tropics:jungle");
+        assert_eq!(biome.get_ground_tile_type(), "grass");
+        assert_eq!(biome.get_spawnable_objects(), vec![("tropics:tree", 0.2)]);
+    }
+
+    #[test]
+    fn bundled_biomes_load() {
+        let biomes = crate::biomes::all(&Content::base().unwrap()).unwrap();
+        assert_eq!(biomes.len(), 7);
+    }
+}
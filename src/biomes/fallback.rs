@@ -0,0 +1,31 @@
+use gaymwtf_core::Biome;
+
+pub const FALLBACK_BIOME: &str = "fallback";
+
+/// Accepts every climate. Registered after all other biomes so a tile that
+/// no real biome claims still gets ground instead of a hole; the coverage
+/// check reports such tiles as gaps.
+#[derive(Clone)]
+pub struct FallbackBiome;
+
+impl Biome for FallbackBiome {
+    fn get_type_tag(&self) -> &'static str {
+        FALLBACK_BIOME
+    }
+
+    fn is_suitable(&self, _height: f64, _moisture: f64, _temperature: f64) -> bool {
+        true
+    }
+
+    fn get_ground_tile_type(&self) -> &'static str {
+        "grass"
+    }
+
+    fn get_spawnable_objects(&self) -> Vec<(&'static str, f32)> {
+        vec![]
+    }
+
+    fn clone_box(&self) -> Box<dyn Biome> {
+        Box::new(self.clone())
+    }
+}
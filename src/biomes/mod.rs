@@ -1,7 +1,31 @@
-pub mod plains;
-pub mod forest;
-pub mod river;
-pub mod beach;
-pub mod snow_plains;
-pub mod snow_forest;
-pub mod desert;
+pub mod climate;
+pub mod data;
+pub mod fallback;
+pub mod validate;
+
+use anyhow::{bail, Context};
+use std::path::Path;
+
+use climate::ClimateBiome;
+
+use crate::content::Content;
+
+/// Every biome in `assets/biomes` and in the `biomes` folder of each of
+/// `content`'s mods, without the fallback. The order does not matter:
+/// overlaps are settled by priority and fit, see [`climate::best_fit`].
+pub fn all(content: &Content) -> anyhow::Result<Vec<Box<dyn ClimateBiome>>> {
+    let (tile_ids, object_ids) = (content.manifest.tile_ids(), content.manifest.object_ids());
+    let mut biomes = data::load_biomes(Path::new(data::BIOMES_DIR), None, &tile_ids, &object_ids)?;
+    if biomes.is_empty() {
+        bail!("No biome files in {}", data::BIOMES_DIR);
+    }
+    for info in &content.mods {
+        let dir = info.dir.join("biomes");
+        if dir.is_dir() {
+            let loaded = data::load_biomes(&dir, Some(info.id()), &tile_ids, &object_ids)
+                .with_context(|| format!("Failed to load the biomes of mod '{}'", info.id()))?;
+            biomes.extend(loaded);
+        }
+    }
+    Ok(biomes)
+}
@@ -0,0 +1,102 @@
+use super::climate::ClimateBiome;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Samples taken along each climate axis; every sample sits in the middle of
+/// its cell, so shared range edges are not counted as overlaps.
+pub const SAMPLES_PER_AXIS: usize = 100;
+/// Example points listed per gap in the report.
+const EXAMPLES: usize = 5;
+
+/// Where the climate space is covered by no biome, or by several.
+#[derive(Debug, Default)]
+pub struct CoverageReport {
+    pub samples: usize,
+    /// Climate points, as (height, moisture, temperature), that no biome accepts.
+    pub gaps: Vec<(f64, f64, f64)>,
+    /// Number of samples accepted by each set of more than one biome. These
+    /// are settled by `climate::best_fit`, so they are not errors.
+    pub overlaps: BTreeMap<Vec<&'static str>, usize>,
+}
+
+impl CoverageReport {
+    pub fn has_gaps(&self) -> bool {
+        !self.gaps.is_empty()
+    }
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.gaps.is_empty() {
+            writeln!(f, "Biomes cover all {} climate samples", self.samples)?;
+        } else {
+            let range = |get: fn(&(f64, f64, f64)) -> f64| {
+                let values = self.gaps.iter().map(get);
+                let min = values.clone().fold(f64::INFINITY, f64::min);
+                let max = values.fold(f64::NEG_INFINITY, f64::max);
+                format!("{:.3}..{:.3}", min, max)
+            };
+            writeln!(
+                f,
+                "{} of {} climate samples have no biome (height {}, moisture {}, temperature {})",
+                self.gaps.len(),
+                self.samples,
+                range(|p| p.0),
+                range(|p| p.1),
+                range(|p| p.2)
+            )?;
+            for (height, moisture, temperature) in self.gaps.iter().take(EXAMPLES) {
+                writeln!(f, "  gap at height {:.3}, moisture {:.3}, temperature {:.3}", height, moisture, temperature)?;
+            }
+        }
+        for (biomes, count) in &self.overlaps {
+            let share = *count as f64 / self.samples as f64 * 100.0;
+            writeln!(f, "  overlap {}: {} samples ({:.1}%)", biomes.join(" + "), count, share)?;
+        }
+        Ok(())
+    }
+}
+
+/// Samples the whole climate cube and records which biomes accept each point.
+pub fn check_coverage(biomes: &[Box<dyn ClimateBiome>]) -> CoverageReport {
+    let axis = |i: usize| (i as f64 + 0.5) / SAMPLES_PER_AXIS as f64;
+    let mut report = CoverageReport { samples: SAMPLES_PER_AXIS.pow(3), ..Default::default() };
+    for h in 0..SAMPLES_PER_AXIS {
+        for m in 0..SAMPLES_PER_AXIS {
+            for t in 0..SAMPLES_PER_AXIS {
+                let (height, moisture, temperature) = (axis(h), axis(m), axis(t));
+                let matching: Vec<&'static str> = biomes
+                    .iter()
+                    .filter(|b| b.is_suitable(height, moisture, temperature))
+                    .map(|b| b.get_type_tag())
+                    .collect();
+                match matching.len() {
+                    0 => report.gaps.push((height, moisture, temperature)),
+                    1 => {}
+                    _ => *report.overlaps.entry(matching).or_default() += 1,
+                }
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biomes::fallback::FALLBACK_BIOME;
+    use crate::content::Content;
+
+    #[test]
+    fn biomes_cover_the_whole_climate_space() {
+        let report = check_coverage(&crate::biomes::all(&Content::base().unwrap()).unwrap());
+        assert!(!report.has_gaps(), "{}", report);
+    }
+
+    #[test]
+    fn fallback_catches_climates_outside_every_range() {
+        let registry = crate::init_biome_registry(&Content::base().unwrap()).unwrap();
+        let biome = registry.find_biome(1.2, -0.1, 0.5).expect("the fallback accepts everything");
+        assert_eq!(biome.get_type_tag(), FALLBACK_BIOME);
+    }
+}
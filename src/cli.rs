@@ -0,0 +1,108 @@
+use anyhow::{anyhow, bail, Context};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::worldgen::seed::parse_seed;
+use crate::worldgen::GeneratorSettings;
+
+/// Options every headless tool accepts to pick the terrain algorithm.
+pub const GENERATOR_OPTIONS: [&str; 5] = ["generator-version", "scale", "octaves", "persistence", "lacunarity"];
+
+pub const GENERATOR_USAGE: &str = "  --generator-version <n>   terrain algorithm, 1 for legacy worlds (default: current)
+  --scale <f>               noise scale (default: 0.003)
+  --octaves <n>             noise octaves (default: 6)
+  --persistence <f>         amplitude falloff per octave (default: 0.8)
+  --lacunarity <f>          frequency growth per octave (default: 2.0)";
+
+/// Command line arguments of the form `--name value` and `--switch`.
+pub struct Args {
+    values: HashMap<String, String>,
+    switches: HashSet<String>,
+}
+
+impl Args {
+    /// Parses `args` (without the program name). Only the given `options`,
+    /// which take a value, and `switches`, which do not, are accepted.
+    pub fn parse(args: impl IntoIterator<Item = String>, options: &[&str], switches: &[&str]) -> anyhow::Result<Self> {
+        let mut values = HashMap::new();
+        let mut found_switches = HashSet::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                bail!("Unexpected argument '{}'", arg);
+            };
+            if switches.contains(&name) {
+                found_switches.insert(name.to_string());
+            } else if options.contains(&name) {
+                let value = args.next().ok_or_else(|| anyhow!("Missing value for --{}", name))?;
+                values.insert(name.to_string(), value);
+            } else {
+                bail!("Unknown option --{}", name);
+            }
+        }
+        Ok(Self { values, switches: found_switches })
+    }
+
+    pub fn switch(&self, name: &str) -> bool {
+        self.switches.contains(name)
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str) -> anyhow::Result<Option<T>>
+    where
+        T::Err: Display,
+    {
+        self.text(name)
+            .map(|value| value.parse().map_err(|e| anyhow!("Invalid value '{}' for --{}: {}", value, name, e)))
+            .transpose()
+    }
+
+    pub fn get_or<T: FromStr>(&self, name: &str, default: T) -> anyhow::Result<T>
+    where
+        T::Err: Display,
+    {
+        Ok(self.get(name)?.unwrap_or(default))
+    }
+
+    /// A comma-separated list of exactly `N` values, e.g. `--chunks -4,-4,3,3`.
+    pub fn get_list<T: FromStr + Copy + Default, const N: usize>(&self, name: &str) -> anyhow::Result<Option<[T; N]>>
+    where
+        T::Err: Display,
+    {
+        let Some(value) = self.text(name) else {
+            return Ok(None);
+        };
+        let parts: Vec<&str> = value.split(',').map(str::trim).collect();
+        if parts.len() != N {
+            bail!("--{} expects {} comma-separated values, got '{}'", name, N, value);
+        }
+        let mut list = [T::default(); N];
+        for (slot, part) in list.iter_mut().zip(parts) {
+            *slot = part.parse().map_err(|e| anyhow!("Invalid value '{}' for --{}: {}", part, name, e))?;
+        }
+        Ok(Some(list))
+    }
+
+    /// The seed from `name`, parsed the same way as the seed field when creating a world.
+    pub fn seed(&self, name: &str) -> anyhow::Result<u64> {
+        self.text(name)
+            .map(parse_seed)
+            .with_context(|| format!("Missing required option --{}", name))
+    }
+
+    /// Generator settings from [`GENERATOR_OPTIONS`], defaulting to those of a new world.
+    pub fn generator_settings(&self) -> anyhow::Result<GeneratorSettings> {
+        let defaults = GeneratorSettings::default();
+        Ok(GeneratorSettings {
+            version: self.get_or("generator-version", defaults.version)?,
+            scale: self.get_or("scale", defaults.scale)?,
+            octaves: self.get_or("octaves", defaults.octaves)?,
+            persistence: self.get_or("persistence", defaults.persistence)?,
+            lacunarity: self.get_or("lacunarity", defaults.lacunarity)?,
+        })
+    }
+}
@@ -0,0 +1,307 @@
+use anyhow::{anyhow, bail, Context};
+use gaymwtf_core::{World, CHUNK_SIZE, TILE_SIZE};
+use macroquad::prelude::*;
+
+use crate::content;
+use crate::utils::metadata::WorldMetadata;
+use crate::worldgen::spawn::{spawn_chunk, spawn_position};
+use crate::worldgen::{generate_chunk, WorldGenerator};
+
+/// Everything a command may read or change.
+pub struct CommandContext<'a> {
+    pub world: &'a mut World,
+    pub metadata: &'a mut WorldMetadata,
+    pub generator: &'a WorldGenerator,
+    /// Set by commands that want the game to save once they finish.
+    pub save_requested: bool,
+    /// Chunks a command changed, which maps have to draw again.
+    pub changed_chunks: Vec<(i32, i32)>,
+}
+
+pub struct Command {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub help: &'static str,
+    /// Words that can follow the command name at argument position `index`.
+    pub completions: fn(index: usize) -> Vec<&'static str>,
+    pub run: fn(&mut CommandContext, &[&str]) -> anyhow::Result<String>,
+}
+
+fn no_completions(_index: usize) -> Vec<&'static str> {
+    Vec::new()
+}
+
+fn first_word(index: usize, words: &[&'static str]) -> Vec<&'static str> {
+    if index == 0 { words.to_vec() } else { Vec::new() }
+}
+
+/// Every console command, looked up by name.
+pub struct CommandRegistry {
+    commands: Vec<Command>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self { commands: Vec::new() };
+        registry.register(Command {
+            name: "help",
+            usage: "help",
+            help: "list every command",
+            completions: no_completions,
+            run: |_, _| Ok(String::new()),
+        });
+        registry.register(Command {
+            name: "tp",
+            usage: "tp <x> <y>",
+            help: "teleport the player to a tile",
+            completions: no_completions,
+            run: teleport,
+        });
+        registry.register(Command {
+            name: "seed",
+            usage: "seed",
+            help: "print the world seed",
+            completions: no_completions,
+            run: |ctx, _| Ok(format!("Seed: {} ({})", ctx.metadata.seed_label(), ctx.metadata.seed)),
+        });
+        registry.register(Command {
+            name: "time",
+            usage: "time [set <seconds>]",
+            help: "print or set the in-game time",
+            completions: |index| first_word(index, &["set"]),
+            run: time,
+        });
+        registry.register(Command {
+            name: "spawn",
+            usage: "spawn <object_id>",
+            help: "place an object on the player's tile",
+            completions: |index| first_word(index, &content::content().map(|c| c.object_ids()).unwrap_or_default()),
+            run: spawn_object,
+        });
+        registry.register(Command {
+            name: "settile",
+            usage: "settile <tile_id>",
+            help: "replace the tile under the player",
+            completions: |index| first_word(index, &content::content().map(|c| c.tile_ids()).unwrap_or_default()),
+            run: set_tile,
+        });
+        registry.register(Command {
+            name: "regen",
+            usage: "regen chunk [x y]",
+            help: "generate a chunk again, keeping the player",
+            completions: |index| first_word(index, &["chunk"]),
+            run: regen_chunk,
+        });
+        registry.register(Command {
+            name: "save",
+            usage: "save",
+            help: "save the world now",
+            completions: no_completions,
+            run: |ctx, _| {
+                ctx.save_requested = true;
+                Ok("Saving world".to_string())
+            },
+        });
+        registry.register(Command {
+            name: "biome",
+            usage: "biome",
+            help: "print the biome and noise values under the player",
+            completions: no_completions,
+            run: biome,
+        });
+        registry
+    }
+
+    pub fn register(&mut self, command: Command) {
+        self.commands.retain(|c| c.name != command.name);
+        self.commands.push(command);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Command> {
+        self.commands.iter().find(|c| c.name == name)
+    }
+
+    /// Candidates for the last word of `line`.
+    pub fn complete(&self, line: &str) -> Vec<&'static str> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let typing_new_word = line.is_empty() || line.ends_with(char::is_whitespace);
+        let prefix = if typing_new_word { "" } else { words.last().copied().unwrap_or("") };
+        let position = if typing_new_word { words.len() } else { words.len() - 1 };
+
+        let candidates: Vec<&'static str> = if position == 0 {
+            self.commands.iter().map(|c| c.name).collect()
+        } else {
+            match self.get(words[0]) {
+                Some(command) => (command.completions)(position - 1),
+                None => Vec::new(),
+            }
+        };
+        candidates.into_iter().filter(|c| c.starts_with(prefix)).collect()
+    }
+
+    /// Runs one command line and returns what it printed.
+    pub fn execute(&self, ctx: &mut CommandContext, line: &str) -> anyhow::Result<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            return Ok(String::new());
+        };
+        // `help` lists the registry itself, which commands cannot see.
+        if name == "help" {
+            let lines: Vec<String> = self.commands.iter().map(|c| format!("{} - {}", c.usage, c.help)).collect();
+            return Ok(lines.join("\n"));
+        }
+        let command = self
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown command '{}', try 'help'", name))?;
+        (command.run)(ctx, args).with_context(|| format!("Usage: {}", command.usage))
+    }
+}
+
+fn parse_arg<T: std::str::FromStr>(args: &[&str], index: usize, what: &str) -> anyhow::Result<T> {
+    let arg = args.get(index).ok_or_else(|| anyhow!("Missing {}", what))?;
+    arg.parse().map_err(|_| anyhow!("Invalid {} '{}'", what, arg))
+}
+
+/// Tile the player stands on, and the chunk that holds the player object.
+fn find_player(world: &World) -> anyhow::Result<((i32, i32), (i32, i32))> {
+    for (&chunk_pos, chunk) in &world.chunks {
+        if let Some(player) = chunk.objects.iter().find(|o| o.get_type_tag() == "player") {
+            let tile = (player.get_pos() / TILE_SIZE + 0.5).floor();
+            return Ok(((tile.x as i32, tile.y as i32), chunk_pos));
+        }
+    }
+    bail!("There is no player in the world")
+}
+
+fn ensure_chunk(ctx: &mut CommandContext, chunk_pos: (i32, i32)) -> anyhow::Result<()> {
+    if !ctx.world.chunks.contains_key(&chunk_pos) {
+        let chunk = futures::executor::block_on(generate_chunk(
+            chunk_pos,
+            ctx.generator,
+            &ctx.world.tile_registry,
+            &ctx.world.object_registry,
+            &ctx.world.biome_registry,
+        ))?;
+        ctx.world.add_chunk(chunk);
+    }
+    Ok(())
+}
+
+fn teleport(ctx: &mut CommandContext, args: &[&str]) -> anyhow::Result<String> {
+    let target: (i32, i32) = (parse_arg(args, 0, "x")?, parse_arg(args, 1, "y")?);
+    let (_, from_chunk) = find_player(ctx.world)?;
+    let to_chunk = spawn_chunk(target);
+    // The world only moves objects into chunks that exist, so load the target first.
+    ensure_chunk(ctx, to_chunk)?;
+    let chunk = ctx.world.chunks.get_mut(&from_chunk).context("Player chunk vanished")?;
+    let index = chunk.objects.iter().position(|o| o.get_type_tag() == "player").context("Player vanished")?;
+    let mut player = chunk.objects.remove(index);
+    player.set_pos(spawn_position(target));
+    ctx.world.chunks.get_mut(&to_chunk).context("Target chunk vanished")?.objects.push(player);
+    ctx.metadata.explored.insert(to_chunk);
+    Ok(format!("Teleported to {} {}", target.0, target.1))
+}
+
+fn time(ctx: &mut CommandContext, args: &[&str]) -> anyhow::Result<String> {
+    match args.first() {
+        None => Ok(format!("Time: {:.0}s", ctx.metadata.world_time)),
+        Some(&"set") => {
+            let seconds: f64 = parse_arg(args, 1, "seconds")?;
+            if seconds < 0.0 {
+                bail!("Time cannot be negative");
+            }
+            ctx.metadata.world_time = seconds;
+            Ok(format!("Time set to {:.0}s", seconds))
+        }
+        Some(other) => bail!("Unknown subcommand '{}'", other),
+    }
+}
+
+fn spawn_object(ctx: &mut CommandContext, args: &[&str]) -> anyhow::Result<String> {
+    let id = *args.first().context("Missing object id")?;
+    let mut object = ctx
+        .world
+        .object_registry
+        .create_object_by_id(id)
+        .ok_or_else(|| anyhow!("Unknown object '{}'", id))?;
+    let (tile, _) = find_player(ctx.world)?;
+    object.set_pos(spawn_position(tile));
+    let chunk_pos = spawn_chunk(tile);
+    ensure_chunk(ctx, chunk_pos)?;
+    ctx.world.chunks.get_mut(&chunk_pos).context("Chunk vanished")?.objects.push(object);
+    ctx.changed_chunks.push(chunk_pos);
+    Ok(format!("Spawned {} at {} {}", id, tile.0, tile.1))
+}
+
+fn set_tile(ctx: &mut CommandContext, args: &[&str]) -> anyhow::Result<String> {
+    let id = *args.first().context("Missing tile id")?;
+    let mut tile = ctx
+        .world
+        .tile_registry
+        .create_tile_by_id(id)
+        .ok_or_else(|| anyhow!("Unknown tile '{}'", id))?;
+    let (pos, _) = find_player(ctx.world)?;
+    let world_pos = spawn_position(pos);
+    tile.set_pos(world_pos);
+    let chunk = ctx.world.chunks.get_mut(&spawn_chunk(pos)).context("The player's chunk is not loaded")?;
+    let slot = chunk
+        .tiles
+        .iter_mut()
+        .find(|t| t.get_pos() == world_pos)
+        .context("The player's chunk has no tile there")?;
+    *slot = tile;
+    ctx.changed_chunks.push(spawn_chunk(pos));
+    Ok(format!("Set tile at {} {} to {}", pos.0, pos.1, id))
+}
+
+fn regen_chunk(ctx: &mut CommandContext, args: &[&str]) -> anyhow::Result<String> {
+    if args.first() != Some(&"chunk") {
+        bail!("Only 'regen chunk' is supported");
+    }
+    let chunk_pos = if args.len() > 1 {
+        (parse_arg(args, 1, "chunk x")?, parse_arg(args, 2, "chunk y")?)
+    } else {
+        let (tile, _) = find_player(ctx.world)?;
+        spawn_chunk(tile)
+    };
+    let mut chunk = futures::executor::block_on(generate_chunk(
+        chunk_pos,
+        ctx.generator,
+        &ctx.world.tile_registry,
+        &ctx.world.object_registry,
+        &ctx.world.biome_registry,
+    ))?;
+    if let Some(old) = ctx.world.chunks.remove(&chunk_pos) {
+        chunk.objects.extend(old.objects.into_iter().filter(|o| o.get_type_tag() == "player"));
+    }
+    ctx.world.add_chunk(chunk);
+    ctx.changed_chunks.push(chunk_pos);
+    Ok(format!("Regenerated chunk {}:{}", chunk_pos.0, chunk_pos.1))
+}
+
+fn biome(ctx: &mut CommandContext, _args: &[&str]) -> anyhow::Result<String> {
+    let (tile, _) = find_player(ctx.world)?;
+    let (height, moisture, temperature) = ctx.generator.get_values(tile.0, tile.1);
+    let name = ctx
+        .world
+        .biome_registry
+        .find_biome(height, moisture, temperature)
+        .map_or("none", |b| b.get_type_tag());
+    Ok(format!(
+        "{} at {} {} (chunk {}:{}): height {:.3}, moisture {:.3}, temperature {:.3}",
+        name,
+        tile.0,
+        tile.1,
+        tile.0.div_euclid(CHUNK_SIZE as i32),
+        tile.1.div_euclid(CHUNK_SIZE as i32),
+        height,
+        moisture,
+        temperature
+    ))
+}
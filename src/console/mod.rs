@@ -0,0 +1,169 @@
+pub mod commands;
+
+pub use commands::{Command, CommandContext, CommandRegistry};
+
+use macroquad::prelude::*;
+
+use crate::ui::TextInput;
+
+const MAX_LINE_LEN: usize = 120;
+const MAX_LOG_LINES: usize = 200;
+const MAX_HISTORY: usize = 50;
+/// Log lines shown above the input.
+const VISIBLE_LINES: usize = 12;
+const LINE_HEIGHT: f32 = 20.0;
+const FONT_SIZE: f32 = 20.0;
+const ERROR_COLOR: Color = Color::new(1.0, 0.45, 0.45, 1.0);
+const ECHO_COLOR: Color = GRAY;
+
+/// The developer console, opened with the backtick key.
+pub struct Console {
+    open: bool,
+    input: TextInput,
+    log: Vec<(String, Color)>,
+    history: Vec<String>,
+    /// Position while browsing history with the arrow keys.
+    history_index: Option<usize>,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            input: TextInput::new(MAX_LINE_LEN)
+                .with_filter(|c| c != '`' && !c.is_control())
+                .with_font_size(FONT_SIZE)
+                .with_placeholder("type 'help'"),
+            log: Vec::new(),
+            history: Vec::new(),
+            history_index: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        if self.open {
+            self.close();
+        } else {
+            self.open = true;
+        }
+    }
+
+    /// Unfocuses the input, so it drops what was typed during play the
+    /// next time the console opens.
+    fn close(&mut self) {
+        self.open = false;
+        self.input.update(false);
+    }
+
+    pub fn print(&mut self, text: &str, color: Color) {
+        self.log.extend(text.lines().map(|line| (line.to_string(), color)));
+        let overflow = self.log.len().saturating_sub(MAX_LOG_LINES);
+        self.log.drain(..overflow);
+    }
+
+    fn input_rect() -> Rect {
+        let height = (VISIBLE_LINES as f32 + 1.0) * LINE_HEIGHT + 16.0;
+        Rect::new(8.0, height - LINE_HEIGHT - 10.0, screen_width() - 16.0, LINE_HEIGHT + 8.0)
+    }
+
+    fn complete(&mut self, registry: &CommandRegistry) {
+        let line = self.input.text().to_string();
+        let candidates = registry.complete(&line);
+        let Some(first) = candidates.first() else {
+            return;
+        };
+        // Extend to the longest prefix all candidates share.
+        let common = candidates.iter().fold(first.to_string(), |common, candidate| {
+            common.chars().zip(candidate.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a).collect()
+        });
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let mut completed = format!("{}{}", &line[..start], common);
+        if candidates.len() == 1 {
+            completed.push(' ');
+        } else {
+            self.print(&candidates.join("  "), ECHO_COLOR);
+        }
+        self.input.set_text(&completed);
+    }
+
+    fn browse_history(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match (self.history_index, older) {
+            (None, true) => Some(self.history.len() - 1),
+            (None, false) => None,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i + 1 < self.history.len() => Some(i + 1),
+            (Some(_), false) => None,
+        };
+        self.history_index = index;
+        let text = index.map_or(String::new(), |i| self.history[i].clone());
+        self.input.set_text(&text);
+    }
+
+    /// Handles typing, completion and history. Returns a command line when
+    /// Enter is pressed; the backtick or Escape closes the console.
+    pub fn update(&mut self, registry: &CommandRegistry) -> Option<String> {
+        if is_key_pressed(KeyCode::GraveAccent) || is_key_pressed(KeyCode::Escape) {
+            self.close();
+            return None;
+        }
+        self.input.rect = Self::input_rect();
+        self.input.update(true);
+        if is_key_pressed(KeyCode::Tab) {
+            self.complete(registry);
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.browse_history(true);
+        }
+        if is_key_pressed(KeyCode::Down) {
+            self.browse_history(false);
+        }
+        if !is_key_pressed(KeyCode::Enter) {
+            return None;
+        }
+        let line = self.input.text().trim().to_string();
+        self.input.set_text("");
+        self.history_index = None;
+        if line.is_empty() {
+            return None;
+        }
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+            let overflow = self.history.len().saturating_sub(MAX_HISTORY);
+            self.history.drain(..overflow);
+        }
+        self.print(&format!("> {}", line), ECHO_COLOR);
+        Some(line)
+    }
+
+    /// Runs `line` and prints its output or error.
+    pub fn run(&mut self, registry: &CommandRegistry, ctx: &mut CommandContext, line: &str) {
+        match registry.execute(ctx, line) {
+            Ok(output) => self.print(&output, WHITE),
+            Err(e) => self.print(&format!("{:#}", e), ERROR_COLOR),
+        }
+    }
+
+    /// Draws the console across the top of the screen. Must be called with the default camera.
+    pub fn draw(&self) {
+        let input = Self::input_rect();
+        draw_rectangle(0.0, 0.0, screen_width(), input.bottom() + 6.0, Color::new(0.0, 0.0, 0.0, 0.75));
+        let first = self.log.len().saturating_sub(VISIBLE_LINES);
+        for (i, (line, color)) in self.log[first..].iter().enumerate() {
+            draw_text(line, 10.0, 8.0 + LINE_HEIGHT * (i as f32 + 1.0), FONT_SIZE, *color);
+        }
+        self.input.draw();
+    }
+}
@@ -0,0 +1,356 @@
+use anyhow::{bail, Context};
+use gaymwtf_core::TILE_SIZE;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use crate::mods::{self, ModInfo};
+use crate::scripting::Scripts;
+use crate::utils::intern::intern;
+
+/// The manifest of every data-driven tile and object, relative to the
+/// working directory.
+pub const CONTENT_MANIFEST: &str = "assets/content.json";
+/// A mod's own manifest, inside its folder.
+pub const MOD_CONTENT: &str = "content.json";
+/// Objects with behaviour of their own, implemented in Rust instead of the manifest.
+pub const BUILTIN_OBJECTS: [&str; 1] = ["player"];
+
+fn default_size() -> [f32; 2] {
+    [TILE_SIZE, TILE_SIZE]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TileDef {
+    pub id: String,
+    /// Path of the texture, relative to the working directory.
+    pub texture: String,
+    #[serde(default = "default_size")]
+    pub size: [f32; 2],
+    /// Whether the player may stand on it; spawn points avoid tiles that are not.
+    #[serde(default = "default_true")]
+    pub walkable: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectDef {
+    pub id: String,
+    /// Path of the texture, relative to the working directory.
+    pub texture: String,
+    #[serde(default = "default_size")]
+    pub size: [f32; 2],
+    /// Whether it stops the player; objects without collision can be walked through.
+    #[serde(default = "default_true")]
+    pub collision: bool,
+    /// Damage dealt to whatever touches it.
+    #[serde(default)]
+    pub damage: i32,
+    /// Path of a script with its behaviour, see [`crate::scripting`].
+    #[serde(default)]
+    pub script: Option<String>,
+}
+
+/// The content of `assets/content.json`, for example:
+///
+/// ```json
+/// {
+///   "tiles": [{ "id": "water", "texture": "assets/textures/tiles/water.png", "walkable": false }],
+///   "objects": [{ "id": "cactus", "texture": "assets/textures/objects/cactus/cactus.png", "collision": false, "damage": 1 }]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    pub tiles: Vec<TileDef>,
+    #[serde(default)]
+    pub objects: Vec<ObjectDef>,
+}
+
+/// Lowercase letters, digits and '_', optionally behind a mod namespace: `tropics:palm_tree`.
+pub fn valid_id(id: &str) -> bool {
+    let valid = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    match id.split_once(':') {
+        Some((namespace, id)) => valid(namespace) && valid(id),
+        None => valid(id),
+    }
+}
+
+impl Manifest {
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    /// Everything wrong with the manifest; empty when it can be used.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut seen = HashSet::new();
+        let entries = self
+            .tiles
+            .iter()
+            .map(|t| ("tile", &t.id, &t.texture, t.size))
+            .chain(self.objects.iter().map(|o| ("object", &o.id, &o.texture, o.size)));
+        for (kind, id, texture, size) in entries {
+            if !valid_id(id) {
+                problems.push(format!("{} id '{}' must be lowercase letters, digits and '_'", kind, id));
+            }
+            if !seen.insert((kind, id.as_str())) {
+                problems.push(format!("{} '{}' is defined twice", kind, id));
+            }
+            if kind == "object" && BUILTIN_OBJECTS.contains(&id.as_str()) {
+                problems.push(format!("object id '{}' is reserved", id));
+            }
+            if !size.iter().all(|v| v.is_finite() && *v > 0.0) {
+                problems.push(format!("{} '{}' has size {:?}, both sides must be above 0", kind, id, size));
+            }
+            if !Path::new(texture).is_file() {
+                problems.push(format!("{} '{}' has a missing texture '{}'", kind, id, texture));
+            }
+        }
+        let mut scripts = Scripts::new();
+        for object in &self.objects {
+            if let Some(Err(e)) = object.script.as_deref().map(|path| scripts.load(path)) {
+                problems.push(format!("object '{}' has a broken script: {:#}", object.id, e));
+            }
+        }
+        problems
+    }
+
+    pub fn tile(&self, id: &str) -> Option<&TileDef> {
+        self.tiles.iter().find(|t| t.id == id)
+    }
+
+    pub fn object(&self, id: &str) -> Option<&ObjectDef> {
+        self.objects.iter().find(|o| o.id == id)
+    }
+
+    pub fn tile_ids(&self) -> Vec<&'static str> {
+        self.tiles.iter().map(|t| intern(&t.id)).collect()
+    }
+
+    /// Ids of the manifest's objects and of the built-in ones.
+    pub fn object_ids(&self) -> Vec<&'static str> {
+        self.objects.iter().map(|o| intern(&o.id)).chain(BUILTIN_OBJECTS).collect()
+    }
+
+    /// Unknown tiles are not walkable.
+    pub fn is_walkable(&self, tile: &str) -> bool {
+        self.tile(tile).is_some_and(|t| t.walkable)
+    }
+}
+
+/// Reads and checks a manifest file.
+pub fn load_manifest(path: &Path) -> anyhow::Result<Manifest> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let manifest = Manifest::parse(&text).with_context(|| format!("Invalid {}", path.display()))?;
+    let problems = manifest.problems();
+    if !problems.is_empty() {
+        bail!("Invalid {}:\n  {}", path.display(), problems.join("\n  "));
+    }
+    Ok(manifest)
+}
+
+/// Reads a mod's `content.json`: ids get the mod's namespace and textures
+/// and scripts are looked up in the mod's folder.
+fn load_mod_manifest(info: &ModInfo, path: &Path) -> anyhow::Result<Manifest> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut manifest = Manifest::parse(&text).with_context(|| format!("Invalid {}", path.display()))?;
+    let namespaced: Vec<&str> = manifest
+        .tiles
+        .iter()
+        .map(|t| &t.id)
+        .chain(manifest.objects.iter().map(|o| &o.id))
+        .filter(|id| id.contains(':'))
+        .map(String::as_str)
+        .collect();
+    if !namespaced.is_empty() {
+        bail!("Ids in {} get the '{}:' namespace added, remove it from: {}", path.display(), info.id(), namespaced.join(", "));
+    }
+    let in_mod_dir = |path: &str| info.dir.join(path).to_string_lossy().into_owned();
+    for tile in &mut manifest.tiles {
+        tile.id = mods::qualify(info.id(), &tile.id);
+        tile.texture = in_mod_dir(&tile.texture);
+    }
+    for object in &mut manifest.objects {
+        object.id = mods::qualify(info.id(), &object.id);
+        object.texture = in_mod_dir(&object.texture);
+        object.script = object.script.as_deref().map(in_mod_dir);
+    }
+    let problems = manifest.problems();
+    if !problems.is_empty() {
+        bail!("Invalid {}:\n  {}", path.display(), problems.join("\n  "));
+    }
+    Ok(manifest)
+}
+
+/// The base manifest with the content of `mods` added in order. Every
+/// problem in every mod is reported, and so is any id defined twice.
+fn merge(mods: &[ModInfo]) -> anyhow::Result<Manifest> {
+    let mut merged = load_manifest(Path::new(CONTENT_MANIFEST))?;
+    let mut sources: HashMap<String, String> = merged
+        .tiles
+        .iter()
+        .map(|t| format!("tile '{}'", t.id))
+        .chain(merged.objects.iter().map(|o| format!("object '{}'", o.id)))
+        .map(|key| (key, "the base game".to_string()))
+        .collect();
+    let mut errors = Vec::new();
+    for info in mods {
+        let path = info.dir.join(MOD_CONTENT);
+        if !path.is_file() {
+            continue;
+        }
+        let manifest = match load_mod_manifest(info, &path) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                errors.push(format!("mod '{}': {:#}", info.id(), e));
+                continue;
+            }
+        };
+        let source = format!("mod '{}'", info.id());
+        let keys = manifest
+            .tiles
+            .iter()
+            .map(|t| format!("tile '{}'", t.id))
+            .chain(manifest.objects.iter().map(|o| format!("object '{}'", o.id)));
+        for key in keys {
+            if let Some(first) = sources.insert(key.clone(), source.clone()) {
+                errors.push(format!("{} from {} conflicts with {}", key, source, first));
+            }
+        }
+        merged.tiles.extend(manifest.tiles);
+        merged.objects.extend(manifest.objects);
+    }
+    if !errors.is_empty() {
+        bail!("Mod conflicts and errors:\n  {}", errors.join("\n  "));
+    }
+    Ok(merged)
+}
+
+/// The base content plus that of some mods: everything one world is built
+/// from. Registries are made from a `Content`, never from what is in use.
+#[derive(Debug, Clone)]
+pub struct Content {
+    pub manifest: Arc<Manifest>,
+    /// The mods, in load order.
+    pub mods: Vec<ModInfo>,
+}
+
+impl Content {
+    /// The base content with that of `mods` added in order.
+    pub fn load(mods: &[ModInfo]) -> anyhow::Result<Self> {
+        Ok(Self { manifest: Arc::new(merge(mods)?), mods: mods.to_vec() })
+    }
+
+    /// The base game without mods, as the tools use it.
+    pub fn base() -> anyhow::Result<Self> {
+        Self::load(&[])
+    }
+}
+
+static CONTENT: RwLock<Option<Arc<Manifest>>> = RwLock::new(None);
+
+/// The manifest of the world in play, for lookups during play such as
+/// spawnable objects and console completion. Until a world is activated this
+/// is [`CONTENT_MANIFEST`], read on first use.
+pub fn content() -> anyhow::Result<Arc<Manifest>> {
+    if let Some(manifest) = CONTENT.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Ok(manifest.clone());
+    }
+    let manifest = Arc::new(load_manifest(Path::new(CONTENT_MANIFEST))?);
+    *CONTENT.write().unwrap_or_else(|e| e.into_inner()) = Some(manifest.clone());
+    Ok(manifest)
+}
+
+/// Makes `content` the content in play.
+pub fn activate(content: &Content) {
+    *CONTENT.write().unwrap_or_else(|e| e.into_inner()) = Some(content.manifest.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn bundled_manifest_is_valid() {
+        let manifest = load_manifest(Path::new(CONTENT_MANIFEST)).unwrap();
+        assert!(manifest.is_walkable("grass"));
+        assert!(!manifest.is_walkable("water"));
+        assert_eq!(manifest.object("tree").map(|o| o.size), Some([16.0, 32.0]));
+        // Trees block the player; a cactus only hurts, like it always did.
+        assert_eq!(manifest.object("tree").map(|o| o.collision), Some(true));
+        assert_eq!(manifest.object("cactus").map(|o| (o.collision, o.damage)), Some((false, 1)));
+        assert!(manifest.object_ids().contains(&"player"));
+    }
+
+    #[test]
+    fn reports_duplicates_bad_sizes_and_reserved_ids() {
+        let manifest = Manifest::parse(
+            r#"{
+                "tiles": [
+                    { "id": "grass", "texture": "assets/textures/tiles/grass.png" },
+                    { "id": "grass", "texture": "assets/textures/tiles/grass.png", "size": [0, 16] }
+                ],
+                "objects": [
+                    { "id": "player", "texture": "nope.png" },
+                    { "id": "frog", "texture": "assets/textures/tiles/grass.png", "script": "nope.rhai" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let problems = manifest.problems().join("\n");
+        assert!(problems.contains("tile 'grass' is defined twice"), "{}", problems);
+        assert!(problems.contains("size [0.0, 16.0]"), "{}", problems);
+        assert!(problems.contains("object id 'player' is reserved"), "{}", problems);
+        assert!(problems.contains("missing texture 'nope.png'"), "{}", problems);
+        assert!(problems.contains("object 'frog' has a broken script"), "{}", problems);
+    }
+
+    fn temp_mod(id: &str, content: &str) -> (TempDir, ModInfo) {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join(id);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("assets/textures/tiles/grass.png", dir.join("palm.png")).unwrap();
+        std::fs::write(dir.join(MOD_CONTENT), content).unwrap();
+        let manifest = serde_json::from_str(&format!(r#"{{ "id": "{}", "name": "Test" }}"#, id)).unwrap();
+        (temp, ModInfo { manifest, dir })
+    }
+
+    #[test]
+    fn mod_content_is_namespaced_and_textures_resolve_in_the_mod() {
+        let (_temp, info) = temp_mod("tropics", r#"{ "objects": [{ "id": "palm_tree", "texture": "palm.png" }] }"#);
+        let merged = merge(std::slice::from_ref(&info)).unwrap();
+        let palm = merged.object("tropics:palm_tree").expect("namespaced id");
+        assert_eq!(Path::new(&palm.texture), info.dir.join("palm.png"));
+        assert!(merged.object("tree").is_some());
+    }
+
+    #[test]
+    fn loading_a_worlds_content_does_not_put_it_in_play() {
+        let (_temp, info) = temp_mod("islands", r#"{ "objects": [{ "id": "palm_tree", "texture": "palm.png" }] }"#);
+        let loaded = Content::load(std::slice::from_ref(&info)).unwrap();
+        assert!(loaded.manifest.object("islands:palm_tree").is_some());
+        assert_eq!(loaded.mods[0].id(), "islands");
+        assert!(content().unwrap().object("islands:palm_tree").is_none());
+    }
+
+    #[test]
+    fn mod_ids_with_a_namespace_are_rejected() {
+        let (_temp, info) = temp_mod("thief", r#"{ "tiles": [{ "id": "tropics:sand", "texture": "palm.png" }] }"#);
+        let error = format!("{:#}", merge(&[info]).unwrap_err());
+        assert!(error.contains("mod 'thief'") && error.contains("tropics:sand"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        assert!(Manifest::parse(r#"{ "tiles": [{ "id": "a", "texture": "a.png", "walkabel": false }] }"#).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use gaymwtf_core::{BiomeRegistry, CHUNK_SIZE, TILE_SIZE};
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+use crate::worldgen::preview::biome_color;
+use crate::worldgen::WorldGenerator;
+
+const TINT_ALPHA: f32 = 0.55;
+const LEGEND_FONT_SIZE: f32 = 18.0;
+const GRADIENT_WIDTH: f32 = 200.0;
+
+/// What the overlay paints over each tile; F3+B steps through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BiomeOverlayMode {
+    #[default]
+    Off,
+    Biome,
+    Height,
+    Moisture,
+    Temperature,
+}
+
+impl BiomeOverlayMode {
+    pub fn next(self) -> Self {
+        match self {
+            BiomeOverlayMode::Off => BiomeOverlayMode::Biome,
+            BiomeOverlayMode::Biome => BiomeOverlayMode::Height,
+            BiomeOverlayMode::Height => BiomeOverlayMode::Moisture,
+            BiomeOverlayMode::Moisture => BiomeOverlayMode::Temperature,
+            BiomeOverlayMode::Temperature => BiomeOverlayMode::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BiomeOverlayMode::Off => "off",
+            BiomeOverlayMode::Biome => "biomes",
+            BiomeOverlayMode::Height => "height",
+            BiomeOverlayMode::Moisture => "moisture",
+            BiomeOverlayMode::Temperature => "temperature",
+        }
+    }
+}
+
+/// Noise values and biome of every tile in a chunk, row by row.
+struct ChunkSample {
+    values: Vec<(f64, f64, f64)>,
+    biomes: Vec<Option<&'static str>>,
+}
+
+impl ChunkSample {
+    fn new(chunk_pos: (i32, i32), generator: &WorldGenerator, biome_registry: &BiomeRegistry) -> Self {
+        let mut values = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE);
+        let mut biomes = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE);
+        for y in 0..CHUNK_SIZE as i32 {
+            for x in 0..CHUNK_SIZE as i32 {
+                let (height, moisture, temperature) =
+                    generator.get_values(chunk_pos.0 * CHUNK_SIZE as i32 + x, chunk_pos.1 * CHUNK_SIZE as i32 + y);
+                values.push((height, moisture, temperature));
+                biomes.push(biome_registry.find_biome(height, moisture, temperature).map(|b| b.get_type_tag()));
+            }
+        }
+        Self { values, biomes }
+    }
+}
+
+/// Blue for 0 through green and yellow to red for 1.
+pub fn heat_color(value: f64) -> Color {
+    let stops = [BLUE, GREEN, YELLOW, RED];
+    let t = value.clamp(0.0, 1.0) as f32 * (stops.len() - 1) as f32;
+    let i = (t.floor() as usize).min(stops.len() - 2);
+    let f = t - i as f32;
+    let (a, b) = (stops[i], stops[i + 1]);
+    Color::new(a.r + (b.r - a.r) * f, a.g + (b.g - a.g) * f, a.b + (b.b - a.b) * f, 1.0)
+}
+
+/// Debug overlay that tints every visible tile by its biome or by one of the
+/// raw noise values behind it, for tuning `Biome::is_suitable` thresholds.
+#[derive(Default)]
+pub struct BiomeOverlay {
+    mode: BiomeOverlayMode,
+    samples: HashMap<(i32, i32), ChunkSample>,
+}
+
+impl BiomeOverlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Switches to the next mode; samples are dropped once the overlay is off.
+    pub fn cycle(&mut self) {
+        self.mode = self.mode.next();
+        if self.mode == BiomeOverlayMode::Off {
+            self.samples.clear();
+        }
+    }
+
+    fn tile_color(mode: BiomeOverlayMode, sample: &ChunkSample, index: usize) -> Option<Color> {
+        let (height, moisture, temperature) = sample.values[index];
+        match mode {
+            BiomeOverlayMode::Off => None,
+            BiomeOverlayMode::Biome => {
+                let [r, g, b, _] = biome_color(sample.biomes[index].unwrap_or(""));
+                Some(Color::from_rgba(r, g, b, 255))
+            }
+            BiomeOverlayMode::Height => Some(heat_color(height)),
+            BiomeOverlayMode::Moisture => Some(heat_color(moisture)),
+            BiomeOverlayMode::Temperature => Some(heat_color(temperature)),
+        }
+    }
+
+    /// Tints the tiles inside the camera's view and forgets chunks more than
+    /// one chunk outside it. Must be called with `camera` set.
+    pub fn draw_world(&mut self, camera: &Camera2D, generator: &WorldGenerator, biome_registry: &BiomeRegistry) {
+        if self.mode == BiomeOverlayMode::Off {
+            return;
+        }
+        let chunk_pixels = CHUNK_SIZE as f32 * TILE_SIZE;
+        let top_left = (camera.screen_to_world(Vec2::ZERO) / chunk_pixels).floor();
+        let bottom_right = (camera.screen_to_world(vec2(screen_width(), screen_height())) / chunk_pixels).floor();
+        let (min, max) = (top_left.as_ivec2() - 1, bottom_right.as_ivec2() + 1);
+        self.samples.retain(|&(cx, cy), _| cx >= min.x && cx <= max.x && cy >= min.y && cy <= max.y);
+
+        for cy in top_left.y as i32..=bottom_right.y as i32 {
+            for cx in top_left.x as i32..=bottom_right.x as i32 {
+                let sample = self
+                    .samples
+                    .entry((cx, cy))
+                    .or_insert_with(|| ChunkSample::new((cx, cy), generator, biome_registry));
+                for index in 0..CHUNK_SIZE * CHUNK_SIZE {
+                    let Some(mut color) = Self::tile_color(self.mode, sample, index) else {
+                        continue;
+                    };
+                    color.a = TINT_ALPHA;
+                    let x = (cx * CHUNK_SIZE as i32 + (index % CHUNK_SIZE) as i32) as f32 * TILE_SIZE;
+                    let y = (cy * CHUNK_SIZE as i32 + (index / CHUNK_SIZE) as i32) as f32 * TILE_SIZE;
+                    draw_rectangle(x, y, TILE_SIZE, TILE_SIZE, color);
+                }
+            }
+        }
+    }
+
+    /// Draws the mode name and a key for its colours in the bottom-left
+    /// corner. Must be called with the default camera.
+    pub fn draw_legend(&self) {
+        if self.mode == BiomeOverlayMode::Off {
+            return;
+        }
+        let x = 10.0;
+        let mut y = screen_height() - 10.0;
+        if self.mode == BiomeOverlayMode::Biome {
+            let mut tags: Vec<&'static str> = self.samples.values().flat_map(|s| s.biomes.iter().flatten().copied()).collect();
+            tags.sort_unstable();
+            tags.dedup();
+            for tag in tags.iter().rev() {
+                let [r, g, b, _] = biome_color(tag);
+                draw_rectangle(x, y - 12.0, 12.0, 12.0, Color::from_rgba(r, g, b, 255));
+                draw_text(tag, x + 18.0, y, LEGEND_FONT_SIZE, WHITE);
+                y -= LEGEND_FONT_SIZE + 2.0;
+            }
+        } else {
+            for i in 0..GRADIENT_WIDTH as usize {
+                draw_rectangle(x + i as f32, y - 12.0, 1.0, 12.0, heat_color(i as f64 / GRADIENT_WIDTH as f64));
+            }
+            draw_text("0", x, y - 16.0, LEGEND_FONT_SIZE, WHITE);
+            draw_text("1", x + GRADIENT_WIDTH - 8.0, y - 16.0, LEGEND_FONT_SIZE, WHITE);
+            y -= LEGEND_FONT_SIZE + 16.0;
+        }
+        let title = format!("Overlay: {} (F3+B)", self.mode.label());
+        draw_text(&title, x, y, LEGEND_FONT_SIZE + 2.0, WHITE);
+    }
+}
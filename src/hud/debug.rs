@@ -0,0 +1,270 @@
+use gaymwtf_core::{World, CHUNK_PIXELS, TILE_SIZE};
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+use crate::assets;
+use crate::utils::system::SystemInfo;
+use crate::worldgen::WorldGenerator;
+
+const FONT_SIZE: f32 = 20.0;
+const LINE_HEIGHT: f32 = 22.0;
+/// Frames kept for the frame-time graph.
+const FRAME_HISTORY: usize = 120;
+/// Chunk generations kept for the timing readout.
+const CHUNK_TIME_HISTORY: usize = 32;
+const GRAPH_HEIGHT: f32 = 60.0;
+/// Frame time, in milliseconds, at the top of the graph.
+const GRAPH_MAX_MS: f32 = 50.0;
+const BACKGROUND: Color = Color::new(0.0, 0.0, 0.0, 0.3);
+
+/// Parts of the overlay that can be shown or hidden with F3 + their number key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugSection {
+    Performance,
+    Position,
+    Worldgen,
+    World,
+    FrameGraph,
+}
+
+impl DebugSection {
+    pub const ALL: [DebugSection; 5] = [
+        DebugSection::Performance,
+        DebugSection::Position,
+        DebugSection::Worldgen,
+        DebugSection::World,
+        DebugSection::FrameGraph,
+    ];
+
+    fn key(self) -> KeyCode {
+        match self {
+            DebugSection::Performance => KeyCode::Key1,
+            DebugSection::Position => KeyCode::Key2,
+            DebugSection::Worldgen => KeyCode::Key3,
+            DebugSection::World => KeyCode::Key4,
+            DebugSection::FrameGraph => KeyCode::Key5,
+        }
+    }
+}
+
+/// What the overlay reads from the running game each frame.
+pub struct DebugContext<'a> {
+    pub world: &'a World,
+    pub generator: &'a WorldGenerator,
+    pub camera: &'a Camera2D,
+    pub player: Option<Vec2>,
+    pub seed: String,
+}
+
+/// Memory in megabytes as a readable size.
+pub fn format_memory(megabytes: u32) -> String {
+    if megabytes < 1024 {
+        format!("{} MB", megabytes)
+    } else {
+        format!("{:.1} GB", megabytes as f32 / 1024.0)
+    }
+}
+
+fn memory_color(megabytes: u32) -> Color {
+    if megabytes < 512 {
+        GREEN
+    } else if megabytes < 1024 {
+        YELLOW
+    } else {
+        RED
+    }
+}
+
+/// The F3 overlay. Pressing F3 alone shows or hides it; holding F3 and
+/// pressing another key is a combo that leaves the visibility alone.
+pub struct DebugOverlay {
+    system_info: SystemInfo,
+    visible: bool,
+    hidden_sections: Vec<DebugSection>,
+    frame_times: VecDeque<f32>,
+    chunk_times: VecDeque<f32>,
+    combo_used: bool,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self {
+            system_info: SystemInfo::new(),
+            visible: false,
+            hidden_sections: Vec::new(),
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY),
+            chunk_times: VecDeque::with_capacity(CHUNK_TIME_HISTORY),
+            combo_used: false,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn is_shown(&self, section: DebugSection) -> bool {
+        !self.hidden_sections.contains(&section)
+    }
+
+    /// True on the frame `key` is pressed while F3 is held.
+    pub fn combo(&mut self, key: KeyCode) -> bool {
+        if is_key_down(KeyCode::F3) && is_key_pressed(key) {
+            self.combo_used = true;
+            return true;
+        }
+        false
+    }
+
+    /// Records the frame and handles F3 and its section combos. Returns true
+    /// when the visibility changed.
+    pub fn update(&mut self, dt: f32) -> bool {
+        self.system_info.update();
+        if self.frame_times.len() == FRAME_HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(dt);
+
+        for section in DebugSection::ALL {
+            if self.combo(section.key()) {
+                if self.is_shown(section) {
+                    self.hidden_sections.push(section);
+                } else {
+                    self.hidden_sections.retain(|s| *s != section);
+                }
+            }
+        }
+        if is_key_released(KeyCode::F3) {
+            let toggled = !self.combo_used;
+            self.combo_used = false;
+            if toggled {
+                self.visible = !self.visible;
+            }
+            return toggled;
+        }
+        false
+    }
+
+    /// Records how long generating one chunk took.
+    pub fn record_chunk_time(&mut self, seconds: f32) {
+        if self.chunk_times.len() == CHUNK_TIME_HISTORY {
+            self.chunk_times.pop_front();
+        }
+        self.chunk_times.push_back(seconds);
+    }
+
+    fn biome_lines(lines: &mut Vec<(String, String, Color)>, label: &str, ctx: &DebugContext, tile: (i32, i32)) {
+        let (height, moisture, temperature) = ctx.generator.get_values(tile.0, tile.1);
+        let biome = ctx
+            .world
+            .biome_registry
+            .find_biome(height, moisture, temperature)
+            .map_or("none", |b| b.get_type_tag());
+        lines.push((format!("{}: ", label), format!("{} @ {}, {}", biome, tile.0, tile.1), WHITE));
+        lines.push((
+            "  H/M/T: ".to_string(),
+            format!("{:.3} / {:.3} / {:.3}", height, moisture, temperature),
+            LIGHTGRAY,
+        ));
+    }
+
+    fn lines(&self, ctx: &DebugContext) -> Vec<(String, String, Color)> {
+        let mut lines = Vec::new();
+        if self.is_shown(DebugSection::Performance) {
+            let fps = self.system_info.fps;
+            let fps_color = if fps >= 45 { GREEN } else if fps >= 30 { YELLOW } else { RED };
+            let cpu = self.system_info.cpu_usage;
+            let cpu_color = if cpu < 10.0 { GREEN } else if cpu < 15.0 { YELLOW } else { RED };
+            let mem = self.system_info.process_memory;
+            lines.push(("FPS: ".to_string(), fps.to_string(), fps_color));
+            lines.push(("CPU: ".to_string(), format!("{:.1}%", cpu), cpu_color));
+            lines.push(("MEM: ".to_string(), format_memory(mem), memory_color(mem)));
+        }
+        if self.is_shown(DebugSection::Position) {
+            lines.push(("SEED: ".to_string(), ctx.seed.clone(), WHITE));
+            if let Some(pos) = ctx.player {
+                lines.push(("POS: ".to_string(), format!("{:.1}, {:.1}", pos.x / TILE_SIZE, pos.y / TILE_SIZE), WHITE));
+                let chunk = (pos / CHUNK_PIXELS).floor();
+                lines.push(("CHUNK: ".to_string(), format!("{}:{}", chunk.x, chunk.y), WHITE));
+            }
+        }
+        if self.is_shown(DebugSection::Worldgen) {
+            if let Some(pos) = ctx.player {
+                let tile = (pos / TILE_SIZE + 0.5).floor();
+                Self::biome_lines(&mut lines, "PLAYER", ctx, (tile.x as i32, tile.y as i32));
+            }
+            let cursor = (ctx.camera.screen_to_world(Vec2::from(mouse_position())) / TILE_SIZE).floor();
+            Self::biome_lines(&mut lines, "CURSOR", ctx, (cursor.x as i32, cursor.y as i32));
+        }
+        if self.is_shown(DebugSection::World) {
+            let objects: usize = ctx.world.chunks.values().map(|c| c.objects.len()).sum();
+            lines.push(("CHUNKS: ".to_string(), ctx.world.chunks.len().to_string(), WHITE));
+            lines.push(("OBJECTS: ".to_string(), objects.to_string(), WHITE));
+            let (loaded, failed) = assets::manager().stats();
+            let color = if failed > 0 { RED } else { WHITE };
+            lines.push(("TEXTURES: ".to_string(), format!("{} loaded, {} missing", loaded, failed), color));
+            if let Some(&last) = self.chunk_times.back() {
+                let average = self.chunk_times.iter().sum::<f32>() / self.chunk_times.len() as f32;
+                let max = self.chunk_times.iter().copied().fold(0.0, f32::max);
+                lines.push((
+                    "CHUNK GEN: ".to_string(),
+                    format!("{:.2} ms (avg {:.2}, max {:.2})", last * 1000.0, average * 1000.0, max * 1000.0),
+                    WHITE,
+                ));
+            }
+        }
+        lines
+    }
+
+    /// Draws the overlay in the top-left corner. Must be called with the default camera.
+    pub fn draw(&self, ctx: &DebugContext) {
+        let x = 10.0;
+        let lines = self.lines(ctx);
+        let width = lines
+            .iter()
+            .map(|(label, value, _)| {
+                measure_text(label, None, FONT_SIZE as u16, 1.0).width + measure_text(value, None, FONT_SIZE as u16, 1.0).width
+            })
+            .fold(190.0, f32::max)
+            + 10.0;
+        let graph = self.is_shown(DebugSection::FrameGraph);
+        let height = lines.len() as f32 * LINE_HEIGHT + if graph { GRAPH_HEIGHT + 10.0 } else { 0.0 } + 8.0;
+        draw_rectangle(5.0, 5.0, width.max(FRAME_HISTORY as f32 + 10.0), height, BACKGROUND);
+
+        let mut y = 20.0;
+        for (label, value, color) in &lines {
+            draw_text(label, x, y, FONT_SIZE, WHITE);
+            let label_width = measure_text(label, None, FONT_SIZE as u16, 1.0).width;
+            draw_text(value, x + label_width, y, FONT_SIZE, *color);
+            y += LINE_HEIGHT;
+        }
+        if graph {
+            self.draw_frame_graph(x, y - LINE_HEIGHT + 12.0);
+        }
+    }
+
+    /// One bar per recent frame; the lines mark 60 and 30 FPS.
+    fn draw_frame_graph(&self, x: f32, top: f32) {
+        let bottom = top + GRAPH_HEIGHT;
+        let bar_height = |ms: f32| (ms / GRAPH_MAX_MS).min(1.0) * GRAPH_HEIGHT;
+        for (i, dt) in self.frame_times.iter().enumerate() {
+            let ms = dt * 1000.0;
+            let color = if ms <= 1000.0 / 45.0 { GREEN } else if ms <= 1000.0 / 30.0 { YELLOW } else { RED };
+            let h = bar_height(ms);
+            draw_rectangle(x + i as f32, bottom - h, 1.0, h, color);
+        }
+        for fps in [60.0, 30.0] {
+            let y = bottom - bar_height(1000.0 / fps);
+            draw_line(x, y, x + FRAME_HISTORY as f32, y, 1.0, Color::new(1.0, 1.0, 1.0, 0.4));
+        }
+    }
+}
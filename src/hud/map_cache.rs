@@ -0,0 +1,110 @@
+use gaymwtf_core::{BiomeRegistry, CHUNK_SIZE};
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+use crate::worldgen::preview::biome_color;
+use crate::worldgen::WorldGenerator;
+
+/// One small texture per chunk with a pixel per tile, coloured by biome.
+/// Chunks are rendered once and then reused by every map that draws them.
+#[derive(Default)]
+pub struct MapCache {
+    textures: HashMap<(i32, i32), Texture2D>,
+}
+
+impl MapCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, chunk_pos: (i32, i32)) -> Option<&Texture2D> {
+        self.textures.get(&chunk_pos)
+    }
+
+    /// Forgets a chunk so it is rendered again, e.g. after its tiles changed.
+    pub fn invalidate(&mut self, chunk_pos: (i32, i32)) {
+        self.textures.remove(&chunk_pos);
+    }
+
+    /// Drops every chunk more than `radius` chunks away from `center`, so the
+    /// cache does not grow with every chunk ever seen.
+    pub fn retain_near(&mut self, center: (i32, i32), radius: i32) {
+        self.textures.retain(|pos, _| (pos.0 - center.0).abs().max((pos.1 - center.1).abs()) <= radius);
+    }
+
+    /// Renders up to `budget` of the given chunks that are not cached yet, so
+    /// a burst of newly loaded chunks is spread over several frames.
+    pub fn build_missing(
+        &mut self,
+        chunks: impl IntoIterator<Item = (i32, i32)>,
+        generator: &WorldGenerator,
+        biome_registry: &BiomeRegistry,
+        budget: usize,
+    ) {
+        let missing: Vec<(i32, i32)> = chunks
+            .into_iter()
+            .filter(|pos| !self.textures.contains_key(pos))
+            .take(budget)
+            .collect();
+        for pos in missing {
+            let texture = Self::render_chunk(pos, generator, biome_registry);
+            self.textures.insert(pos, texture);
+        }
+    }
+
+    fn render_chunk(chunk_pos: (i32, i32), generator: &WorldGenerator, biome_registry: &BiomeRegistry) -> Texture2D {
+        let mut bytes = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE * 4);
+        for y in 0..CHUNK_SIZE as i32 {
+            for x in 0..CHUNK_SIZE as i32 {
+                let world_x = chunk_pos.0 * CHUNK_SIZE as i32 + x;
+                let world_y = chunk_pos.1 * CHUNK_SIZE as i32 + y;
+                let (height, moisture, temperature) = generator.get_values(world_x, world_y);
+                let color = biome_registry
+                    .find_biome(height, moisture, temperature)
+                    .map_or([0, 0, 0, 255], |biome| biome_color(biome.get_type_tag()));
+                bytes.extend_from_slice(&color);
+            }
+        }
+        let texture = Texture2D::from_rgba8(CHUNK_SIZE as u16, CHUNK_SIZE as u16, &bytes);
+        texture.set_filter(FilterMode::Nearest);
+        texture
+    }
+
+    /// Draws every cached chunk in `chunks` that overlaps `view`, where
+    /// `origin` is the screen position of tile (0, 0) and `scale` the size of
+    /// a tile in pixels. Chunks crossing the edge of `view` are cropped.
+    pub fn draw_chunks(&self, chunks: impl IntoIterator<Item = (i32, i32)>, view: Rect, origin: Vec2, scale: f32) {
+        let chunk_pixels = CHUNK_SIZE as f32 * scale;
+        for pos in chunks {
+            let Some(texture) = self.textures.get(&pos) else {
+                continue;
+            };
+            let dest = Rect::new(
+                origin.x + pos.0 as f32 * chunk_pixels,
+                origin.y + pos.1 as f32 * chunk_pixels,
+                chunk_pixels,
+                chunk_pixels,
+            );
+            let Some(visible) = dest.intersect(view) else {
+                continue;
+            };
+            let source = Rect::new(
+                (visible.x - dest.x) / scale,
+                (visible.y - dest.y) / scale,
+                visible.w / scale,
+                visible.h / scale,
+            );
+            draw_texture_ex(
+                texture,
+                visible.x,
+                visible.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(visible.size()),
+                    source: Some(source),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use gaymwtf_core::CHUNK_SIZE;
+use macroquad::prelude::*;
+
+use crate::hud::map_cache::MapCache;
+use crate::utils::metadata::Waypoint;
+
+/// Side of the minimap square, in screen pixels.
+const MINIMAP_SIZE: f32 = 160.0;
+const MARGIN: f32 = 10.0;
+/// Screen pixels per tile at each zoom level.
+const ZOOM_LEVELS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+const DEFAULT_ZOOM: usize = 1;
+pub const WAYPOINT_COLOR: Color = Color::new(1.0, 0.3, 0.3, 1.0);
+
+pub struct Minimap {
+    zoom: usize,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Minimap {
+    pub fn new() -> Self {
+        Self { zoom: DEFAULT_ZOOM }
+    }
+
+    fn scale(&self) -> f32 {
+        ZOOM_LEVELS[self.zoom]
+    }
+
+    /// Handles the zoom keys.
+    pub fn update(&mut self) {
+        if is_key_pressed(KeyCode::Equal) {
+            self.zoom = (self.zoom + 1).min(ZOOM_LEVELS.len() - 1);
+        }
+        if is_key_pressed(KeyCode::Minus) {
+            self.zoom = self.zoom.saturating_sub(1);
+        }
+    }
+
+    /// Draws the minimap in the top-right corner, centered on `player` (in
+    /// tiles), with an arrow along `facing` and a dot for every waypoint.
+    /// Must be called with the default (screen) camera.
+    pub fn draw(
+        &self,
+        cache: &MapCache,
+        loaded_chunks: impl IntoIterator<Item = (i32, i32)>,
+        player: Vec2,
+        facing: Vec2,
+        waypoints: &[Waypoint],
+    ) {
+        let view = Rect::new(screen_width() - MINIMAP_SIZE - MARGIN, MARGIN, MINIMAP_SIZE, MINIMAP_SIZE);
+        let center = view.center();
+        let scale = self.scale();
+        let origin = center - player * scale;
+
+        draw_rectangle(view.x - 2.0, view.y - 2.0, view.w + 4.0, view.h + 4.0, Color::new(0.0, 0.0, 0.0, 0.6));
+        cache.draw_chunks(loaded_chunks, view, origin, scale);
+
+        for waypoint in waypoints {
+            let pos = origin + (vec2(waypoint.x as f32, waypoint.y as f32) + 0.5) * scale;
+            // Waypoints off the map stick to its edge so their direction stays visible.
+            let pos = pos.clamp(view.point() + 3.0, view.point() + view.size() - 3.0);
+            draw_circle(pos.x, pos.y, 3.0, WAYPOINT_COLOR);
+        }
+
+        draw_player_arrow(center, facing, 1.0);
+
+        let zoom_text = format!("{} chunks", (MINIMAP_SIZE / (CHUNK_SIZE as f32 * scale)).round());
+        draw_text(&zoom_text, view.x + 4.0, view.bottom() - 4.0, 16.0, WHITE);
+    }
+}
+
+/// A small arrow at `center` pointing along `facing`, `size` times the minimap one.
+pub fn draw_player_arrow(center: Vec2, facing: Vec2, size: f32) {
+    let tip = center + facing * 7.0 * size;
+    let side = vec2(-facing.y, facing.x) * 4.0 * size;
+    let back = center - facing * 3.0 * size;
+    draw_triangle(tip, back + side, back - side, WHITE);
+    draw_triangle_lines(tip, back + side, back - side, 1.0, BLACK);
+}
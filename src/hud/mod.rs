@@ -0,0 +1,11 @@
+pub mod biome_overlay;
+pub mod debug;
+pub mod map_cache;
+pub mod minimap;
+pub mod world_map;
+
+pub use biome_overlay::{BiomeOverlay, BiomeOverlayMode};
+pub use debug::{DebugContext, DebugOverlay, DebugSection};
+pub use map_cache::MapCache;
+pub use minimap::Minimap;
+pub use world_map::WorldMap;
@@ -0,0 +1,194 @@
+use gaymwtf_core::{BiomeRegistry, CHUNK_SIZE};
+use macroquad::prelude::*;
+use std::collections::BTreeSet;
+
+use crate::hud::map_cache::MapCache;
+use crate::hud::minimap::{draw_player_arrow, WAYPOINT_COLOR};
+use crate::ui::TextInput;
+use crate::utils::metadata::Waypoint;
+use crate::worldgen::WorldGenerator;
+
+/// Screen pixels per tile when the map opens, and the zoom limits.
+const DEFAULT_SCALE: f32 = 2.0;
+const MIN_SCALE: f32 = 0.25;
+const MAX_SCALE: f32 = 8.0;
+const ZOOM_STEP: f32 = 1.25;
+/// Keyboard panning speed in screen pixels per second.
+const PAN_SPEED: f32 = 500.0;
+/// Explored chunks rendered into the cache per frame at most.
+const CHUNKS_PER_FRAME: usize = 32;
+/// How close, in screen pixels, the cursor must be to a marker to pick it.
+const MARKER_PICK_RADIUS: f32 = 8.0;
+const MAX_MARKER_NAME_LEN: usize = 24;
+const BACKGROUND: Color = Color::new(0.05, 0.05, 0.07, 0.95);
+
+/// A marker being named before it is placed.
+struct PendingMarker {
+    tile: (i32, i32),
+    input: TextInput,
+}
+
+/// Full-screen map of every chunk the player has explored. Unexplored chunks
+/// stay dark.
+pub struct WorldMap {
+    open: bool,
+    /// Tile shown at the center of the screen.
+    center: Vec2,
+    /// Screen pixels per tile.
+    scale: f32,
+    last_mouse: Vec2,
+    pending: Option<PendingMarker>,
+}
+
+impl Default for WorldMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorldMap {
+    pub fn new() -> Self {
+        Self { open: false, center: Vec2::ZERO, scale: DEFAULT_SCALE, last_mouse: Vec2::ZERO, pending: None }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Opens the map centered on `player` (in tiles).
+    pub fn open(&mut self, player: Vec2) {
+        self.open = true;
+        self.center = player;
+        self.pending = None;
+        self.last_mouse = Vec2::from(mouse_position());
+    }
+
+    fn screen_center() -> Vec2 {
+        vec2(screen_width(), screen_height()) / 2.0
+    }
+
+    fn to_screen(&self, tile: Vec2) -> Vec2 {
+        Self::screen_center() + (tile - self.center) * self.scale
+    }
+
+    fn to_tile(&self, screen: Vec2) -> Vec2 {
+        self.center + (screen - Self::screen_center()) / self.scale
+    }
+
+    /// Explored chunks that overlap the screen.
+    fn visible_chunks<'a>(&self, explored: &'a BTreeSet<(i32, i32)>) -> impl Iterator<Item = (i32, i32)> + 'a {
+        let chunk_size = CHUNK_SIZE as f32;
+        let top_left = self.to_tile(Vec2::ZERO) / chunk_size;
+        let bottom_right = self.to_tile(vec2(screen_width(), screen_height())) / chunk_size;
+        let (x0, y0) = (top_left.x.floor() as i32, top_left.y.floor() as i32);
+        let (x1, y1) = (bottom_right.x.floor() as i32, bottom_right.y.floor() as i32);
+        explored
+            .range((x0, i32::MIN)..=(x1, i32::MAX))
+            .filter(move |(_, y)| (y0..=y1).contains(y))
+            .copied()
+    }
+
+    fn marker_at(&self, waypoints: &[Waypoint], screen: Vec2) -> Option<usize> {
+        waypoints.iter().position(|waypoint| {
+            let pos = self.to_screen(vec2(waypoint.x as f32, waypoint.y as f32) + 0.5);
+            pos.distance(screen) <= MARKER_PICK_RADIUS
+        })
+    }
+
+    /// Handles panning, zooming and markers, and caches newly visible chunks.
+    pub fn update(
+        &mut self,
+        dt: f32,
+        cache: &mut MapCache,
+        explored: &BTreeSet<(i32, i32)>,
+        generator: &WorldGenerator,
+        biome_registry: &BiomeRegistry,
+        waypoints: &mut Vec<Waypoint>,
+    ) {
+        cache.build_missing(self.visible_chunks(explored), generator, biome_registry, CHUNKS_PER_FRAME);
+        let mouse = Vec2::from(mouse_position());
+        let mouse_delta = mouse - self.last_mouse;
+        self.last_mouse = mouse;
+
+        if let Some(pending) = &mut self.pending {
+            pending.input.rect = Rect::new(screen_width() / 2.0 - 150.0, screen_height() / 2.0 - 18.0, 300.0, 36.0);
+            pending.input.update(true);
+            if is_key_pressed(KeyCode::Enter) {
+                let name = pending.input.text().trim().to_string();
+                if !name.is_empty() {
+                    waypoints.push(Waypoint { name, x: pending.tile.0, y: pending.tile.1 });
+                }
+                self.pending = None;
+            } else if is_key_pressed(KeyCode::Escape) {
+                self.pending = None;
+            }
+            return;
+        }
+
+        if is_key_pressed(KeyCode::M) || is_key_pressed(KeyCode::Escape) {
+            self.open = false;
+            return;
+        }
+
+        let mut pan = Vec2::ZERO;
+        if is_key_down(KeyCode::W) || is_key_down(KeyCode::Up) { pan.y -= 1.0; }
+        if is_key_down(KeyCode::S) || is_key_down(KeyCode::Down) { pan.y += 1.0; }
+        if is_key_down(KeyCode::A) || is_key_down(KeyCode::Left) { pan.x -= 1.0; }
+        if is_key_down(KeyCode::D) || is_key_down(KeyCode::Right) { pan.x += 1.0; }
+        self.center += pan * PAN_SPEED * dt / self.scale;
+        if is_mouse_button_down(MouseButton::Left) {
+            self.center -= mouse_delta / self.scale;
+        }
+
+        let wheel = mouse_wheel().1;
+        if wheel != 0.0 {
+            // Zoom around the cursor so the tile under it stays in place.
+            let anchor = self.to_tile(mouse);
+            let factor = if wheel > 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+            self.scale = (self.scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+            self.center = anchor - (mouse - Self::screen_center()) / self.scale;
+        }
+
+        if is_mouse_button_pressed(MouseButton::Right) {
+            if let Some(index) = self.marker_at(waypoints, mouse) {
+                waypoints.remove(index);
+            } else {
+                let tile = self.to_tile(mouse).floor();
+                let mut input = TextInput::new(MAX_MARKER_NAME_LEN).with_placeholder("Marker name");
+                input.set_text(&format!("Marker {}", waypoints.len() + 1));
+                self.pending = Some(PendingMarker { tile: (tile.x as i32, tile.y as i32), input });
+            }
+        }
+    }
+
+    /// Draws the map over the whole screen. Must be called with the default camera.
+    pub fn draw(&self, cache: &MapCache, explored: &BTreeSet<(i32, i32)>, player: Vec2, facing: Vec2, waypoints: &[Waypoint]) {
+        let screen = Rect::new(0.0, 0.0, screen_width(), screen_height());
+        draw_rectangle(screen.x, screen.y, screen.w, screen.h, BACKGROUND);
+        cache.draw_chunks(self.visible_chunks(explored), screen, self.to_screen(Vec2::ZERO), self.scale);
+
+        let mouse = Vec2::from(mouse_position());
+        let hovered = self.marker_at(waypoints, mouse);
+        for (i, waypoint) in waypoints.iter().enumerate() {
+            let pos = self.to_screen(vec2(waypoint.x as f32, waypoint.y as f32) + 0.5);
+            let radius = if hovered == Some(i) { 6.0 } else { 4.0 };
+            draw_circle(pos.x, pos.y, radius, WAYPOINT_COLOR);
+            draw_text(&waypoint.name, pos.x + 8.0, pos.y + 5.0, 18.0, WHITE);
+        }
+        draw_player_arrow(self.to_screen(player), facing, 1.5);
+
+        let tile = self.to_tile(mouse).floor();
+        let chunk = (tile / CHUNK_SIZE as f32).floor();
+        let readout = format!("X: {}  Y: {}  CHUNK: {}:{}", tile.x, tile.y, chunk.x, chunk.y);
+        draw_text(&readout, 10.0, screen.h - 34.0, 20.0, WHITE);
+        let hint = "Drag / WASD - pan   Wheel - zoom   Right click - add or remove marker   M - close";
+        draw_text(hint, 10.0, screen.h - 12.0, 18.0, GRAY);
+
+        if let Some(pending) = &self.pending {
+            let r = pending.input.rect;
+            draw_rectangle(r.x - 10.0, r.y - 34.0, r.w + 20.0, r.h + 64.0, Color::new(0.0, 0.0, 0.0, 0.8));
+            draw_text("New marker (Enter - place, Esc - cancel)", r.x, r.y - 10.0, 18.0, WHITE);
+            pending.input.draw();
+        }
+    }
+}
@@ -0,0 +1,95 @@
+pub mod assets;
+pub mod biomes;
+pub mod cli;
+pub mod console;
+pub mod content;
+pub mod hud;
+pub mod mods;
+pub mod objects;
+pub mod packs;
+pub mod player;
+pub mod scripting;
+pub mod tiles;
+pub mod worldgen;
+pub mod menus;
+pub mod utils;
+pub mod ui;
+
+use gaymwtf_core::{BiomeRegistry, ObjectRegistry, TileRegistry};
+use macroquad::prelude::*;
+extern crate serde;
+extern crate serde_json;
+
+use biomes::{climate::RankedBiome, fallback::FallbackBiome};
+use content::{Content, Manifest};
+use objects::DataObject;
+use player::{Player, PlayerTextures};
+use scripting::Scripts;
+use tiles::DataTile;
+
+pub async fn register_tiles(registry: &mut TileRegistry, manifest: &Manifest) -> anyhow::Result<()> {
+    for def in &manifest.tiles {
+        registry.register(DataTile::new(def, Vec2::ZERO));
+    }
+    Ok(())
+}
+
+/// Registers the manifest's objects, with their scripts loaded into `scripts`.
+pub async fn register_objects(registry: &mut ObjectRegistry, manifest: &Manifest, scripts: &mut Scripts) -> anyhow::Result<()> {
+    for def in &manifest.objects {
+        registry.register(DataObject::new(def, Vec2::ZERO, scripts));
+    }
+    registry.register(Player::new(Vec2::ZERO, PlayerTextures::new()));
+    Ok(())
+}
+
+pub async fn register_biomes(registry: &mut BiomeRegistry, content: &Content) -> anyhow::Result<()> {
+    for biome in RankedBiome::rank(biomes::all(content)?) {
+        registry.register(biome);
+    }
+    registry.register(FallbackBiome);
+    Ok(())
+}
+
+/// Loads the biome files of `content`, checks that they leave no climate to
+/// the fallback and prints what was found. Broken files are an error; in
+/// debug builds gaps are one too.
+pub fn validate_biomes(content: &Content) -> anyhow::Result<()> {
+    let report = biomes::validate::check_coverage(&biomes::all(content)?);
+    if report.has_gaps() {
+        if cfg!(debug_assertions) {
+            anyhow::bail!("Biome coverage has gaps:\n{}", report);
+        }
+        eprintln!("ERROR: biome coverage has gaps, the fallback biome fills them:\n{}", report);
+    } else {
+        print!("{}", report);
+    }
+    Ok(())
+}
+
+/// The biomes of `content`; fails on broken biome files.
+pub fn init_biome_registry(content: &Content) -> anyhow::Result<BiomeRegistry> {
+    let mut biome_registry = BiomeRegistry::new();
+    futures::executor::block_on(register_biomes(&mut biome_registry, content))?;
+    Ok(biome_registry)
+}
+
+/// Every registry of `content`, for tools and world setup that never run
+/// scripts; fails on broken content files.
+pub fn init_registries(content: &Content) -> anyhow::Result<(TileRegistry, ObjectRegistry, BiomeRegistry)> {
+    let mut tile_registry = TileRegistry::new();
+    let mut object_registry = ObjectRegistry::new();
+    futures::executor::block_on(register_tiles(&mut tile_registry, &content.manifest))?;
+    futures::executor::block_on(register_objects(&mut object_registry, &content.manifest, &mut Scripts::new()))?;
+    Ok((tile_registry, object_registry, init_biome_registry(content)?))
+}
+
+pub fn update_camera(camera: &mut Camera2D) {
+    let base_zoom = 0.0066668;
+    let aspect_ratio = screen_width() / screen_height();
+    camera.zoom = if aspect_ratio > 1.0 {
+        vec2(base_zoom / aspect_ratio, base_zoom)
+    } else {
+        vec2(base_zoom, base_zoom * aspect_ratio)
+    };
+}
@@ -1,34 +1,22 @@
-use gaymwtf_core::{Menu, MenuAction, DrawBatch};
+use gaymwtf_core::DrawBatch;
+use crate::menus::navigation::{Navigation, Screen};
 use macroquad::prelude::*;
+use crate::ui::{Button, FocusRing, Label};
 
 pub struct AboutMenu {
-    back_hovered: bool,
+    lines: Vec<Label>,
+    back: Button,
+    focus: FocusRing,
 }
 
-impl AboutMenu {
-    pub fn new() -> Self {
-        Self { back_hovered: false }
-    }
-    fn back_rect(&self, screen_w: f32, screen_h: f32) -> Rect {
-        Rect::new(screen_w / 2.0 - 80.0, screen_h - 120.0, 160.0, 50.0)
+impl Default for AboutMenu {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Menu for AboutMenu {
-    fn update(&mut self, _dt: f32) -> MenuAction {
-        let (mx, my) = mouse_position();
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-        self.back_hovered = self.back_rect(screen_w, screen_h).contains(vec2(mx, my));
-        if self.back_hovered && is_mouse_button_pressed(MouseButton::Left) {
-            return MenuAction::ChangeState("start".to_string());
-        }
-        MenuAction::None
-    }
-    fn draw(&mut self, _batch: &mut DrawBatch) {
-        clear_background(BLACK);
-        let screen_w = screen_width();
-        let _screen_h = screen_height();
+impl AboutMenu {
+    pub fn new() -> Self {
         let lines = [
             "GaymWTF",
             "Version: 0.0.0.0.0.0.1-alpha-beta-gamma-delta-epsilon-zeta-eta-theta-iota-kappa-lambda-mu-nu-xi-omicron-pi-rho-sigma-tau-upslon-phi-chi-psi-omega",
@@ -36,25 +24,35 @@ impl Menu for AboutMenu {
             "  - bonnie",
             "  - furka",
         ];
+        Self {
+            lines: lines.iter().map(|l| Label::new(l, 32.0)).collect(),
+            back: Button::new("Back"),
+            focus: FocusRing::new(1),
+        }
+    }
+
+    fn layout(&mut self) {
+        self.back.rect = Rect::new(screen_width() / 2.0 - 80.0, screen_height() - 120.0, 160.0, 50.0);
+    }
+}
+
+impl Screen for AboutMenu {
+    fn update(&mut self, _dt: f32) -> Navigation {
+        self.layout();
+        self.focus.update(&[self.back.rect]);
+        if self.back.update(self.focus.is_focused(0)) || is_key_pressed(KeyCode::Escape) {
+            return Navigation::Pop;
+        }
+        Navigation::None
+    }
+    fn draw(&mut self, _batch: &mut DrawBatch) {
+        clear_background(BLACK);
+        self.layout();
         let start_y = 120.0;
         let line_height = 40.0;
-        for (i, line) in lines.iter().enumerate() {
-            draw_text(
-                line,
-                screen_w / 2.0 - 200.0,
-                start_y + i as f32 * line_height,
-                32.0,
-                WHITE,
-            );
+        for (i, line) in self.lines.iter().enumerate() {
+            line.draw(screen_width() / 2.0 - 200.0, start_y + i as f32 * line_height);
         }
-        let color = if self.back_hovered { YELLOW } else { WHITE };
-        draw_text(
-            "Back",
-            screen_w / 2.0 - 60.0,
-            _screen_h - 90.0,
-            36.0,
-            color,
-        );
+        self.back.draw();
     }
-    fn name(&self) -> &str { "about" }
-} 
\ No newline at end of file
+} 
@@ -1,131 +1,126 @@
-use gaymwtf_core::{Menu, MenuAction, DrawBatch};
+use gaymwtf_core::DrawBatch;
 use macroquad::prelude::*;
-use std::fs;
+use crate::menus::navigation::{Navigation, Screen};
+use crate::utils::metadata::GameMode;
+use crate::utils::saves::MAX_WORLD_NAME_LEN;
+use crate::worldgen::seed::MAX_SEED_LEN;
+use crate::ui::{split_row, style, Button, Column, FocusRing, Label, TextInput};
 
 pub struct CreateWorldMenu {
-    pub world_name: String,
-    pub seed_input: String,
-    selected_field: usize,
-    error_message: Option<String>,
+    title: Label,
+    name_label: Label,
+    seed_label: Label,
+    name_input: TextInput,
+    seed_input: TextInput,
+    mode_label: Label,
+    mode: Button,
+    game_mode: GameMode,
+    create: Button,
+    back: Button,
+    focus: FocusRing,
+    error_message: Option<Label>,
+}
+
+impl Default for CreateWorldMenu {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CreateWorldMenu {
     pub fn new() -> Self {
+        let mut focus = FocusRing::new(5);
+        focus.focus(Some(0));
         Self {
-            world_name: String::new(),
-            seed_input: String::new(),
-            selected_field: 0,
+            title: Label::new("Creating new world", 36.0),
+            name_label: Label::new("World Name:", 28.0),
+            seed_label: Label::new("Seed:", 28.0),
+            name_input: TextInput::new(MAX_WORLD_NAME_LEN),
+            seed_input: TextInput::new(MAX_SEED_LEN).with_placeholder("random"),
+            mode_label: Label::new("Mode:", 28.0),
+            mode: Button::new(GameMode::default().label()).with_font_size(24.0),
+            game_mode: GameMode::default(),
+            create: Button::new("Create").with_font_size(32.0),
+            back: Button::new("Back").with_font_size(32.0),
+            focus,
             error_message: None,
         }
     }
-    fn field_rect(&self, field: usize, center_x: f32, y: f32) -> Rect {
-        match field {
-            0 => Rect::new(center_x + 160.0, y - 28.0, 200.0, 36.0),
-            1 => Rect::new(center_x + 160.0, y - 28.0, 200.0, 36.0),
-            2 => Rect::new(center_x, y, 180.0, 48.0),
-            3 => Rect::new(center_x + 220.0, y, 140.0, 48.0),
-            _ => Rect::new(0.0, 0.0, 0.0, 0.0),
-        }
+
+    fn layout(&mut self) {
+        let center_x = screen_width() / 2.0 - 210.0;
+        let mut column = Column::new(center_x + 160.0, 192.0, 260.0, 14.0);
+        self.name_input.rect = column.next(36.0);
+        self.seed_input.rect = column.next(36.0);
+        self.mode.rect = column.next(36.0);
+        column.skip(25.0);
+        let row = column.next(48.0);
+        let buttons = split_row(Rect::new(center_x, row.y, 420.0, row.h), 2, 40.0);
+        self.create.rect = buttons[0];
+        self.back.rect = buttons[1];
     }
-}
 
-impl Menu for CreateWorldMenu {
-    fn update(&mut self, _dt: f32) -> MenuAction {
-        let (mx, my) = mouse_position();
-        let screen_w = screen_width();
-        let _screen_h = screen_height();
-        let center_x = screen_w / 2.0 - 180.0;
-        let mut y = 120.0 + 50.0 * 2.0;
+    fn set_error(&mut self, message: &str) {
+        self.error_message = Some(Label::new(message, 24.0).with_color(style::ERROR_COLOR));
+    }
 
-        if self.field_rect(0, center_x, y).contains(vec2(mx, my)) && is_mouse_button_pressed(MouseButton::Left) {
-            self.selected_field = 0;
+    fn create_world(&mut self) -> Navigation {
+        let name = self.name_input.text().trim().to_string();
+        if name.is_empty() {
+            self.set_error("World name cannot be empty");
+            return Navigation::None;
         }
-        y += 50.0;
-        if self.field_rect(1, center_x, y).contains(vec2(mx, my)) && is_mouse_button_pressed(MouseButton::Left) {
-            self.selected_field = 1;
-        }
-        y += 50.0 * 1.5;
-        if self.field_rect(2, center_x, y).contains(vec2(mx, my)) && is_mouse_button_pressed(MouseButton::Left) {
-            self.selected_field = 2;
-            let name = self.world_name.trim();
-            if name.is_empty() {
-                self.error_message = Some("World name cannot be empty".to_string());
-                return MenuAction::None;
-            }
-            let seed = if self.seed_input.trim().is_empty() {
-                rand::gen_range(0, u32::MAX)
-            } else {
-                match self.seed_input.trim().parse::<u32>() {
-                    Ok(s) => s,
-                    Err(_) => {
-                        self.error_message = Some("Seed must be a number".to_string());
-                        return MenuAction::None;
-                    }
-                }
-            };
-            let save_dir = format!("saves/{}", name);
-            if let Err(e) = fs::create_dir_all(&save_dir) {
-                self.error_message = Some(format!("Error creating folder: {}", e));
-                return MenuAction::None;
+        match crate::worldgen::create_world(&name, self.seed_input.text(), self.game_mode) {
+            Ok(_) => Navigation::Pop,
+            Err(e) => {
+                self.set_error(&format!("{:#}", e));
+                Navigation::None
             }
-            return MenuAction::ChangeState(format!("createworld:{}:{}", name, seed));
-        }
-        if self.field_rect(3, center_x, y).contains(vec2(mx, my)) && is_mouse_button_pressed(MouseButton::Left) {
-            self.selected_field = 3;
-            return MenuAction::ChangeState("worlds".to_string());
         }
+    }
+}
 
-        if self.selected_field == 0 {
-            if let Some(c) = get_char_pressed() {
-                if c.is_ascii() && (c.is_alphanumeric() || c == ' ') && self.world_name.len() < 20 {
-                    self.world_name.push(c);
-                }
-            }
-            if is_key_pressed(KeyCode::Backspace) && !self.world_name.is_empty() {
-                self.world_name.pop();
-            }
+impl Screen for CreateWorldMenu {
+    fn update(&mut self, _dt: f32) -> Navigation {
+        self.layout();
+        self.focus.update(&[
+            self.name_input.rect,
+            self.seed_input.rect,
+            self.mode.rect,
+            self.create.rect,
+            self.back.rect,
+        ]);
+        self.name_input.update(self.focus.is_focused(0));
+        self.seed_input.update(self.focus.is_focused(1));
+        let in_field = self.focus.is_focused(0) || self.focus.is_focused(1);
+        if self.mode.update(self.focus.is_focused(2)) {
+            self.game_mode = self.game_mode.next();
+            self.mode.label = self.game_mode.label().to_string();
         }
-        if self.selected_field == 1 {
-            if let Some(c) = get_char_pressed() {
-                if c.is_ascii() && c.is_numeric() && self.seed_input.len() < 10 {
-                    self.seed_input.push(c);
-                }
-            }
-            if is_key_pressed(KeyCode::Backspace) && !self.seed_input.is_empty() {
-                self.seed_input.pop();
-            }
+        if self.create.update(self.focus.is_focused(3)) || (in_field && is_key_pressed(KeyCode::Enter)) {
+            return self.create_world();
         }
-        MenuAction::None
+        if self.back.update(self.focus.is_focused(4)) || is_key_pressed(KeyCode::Escape) {
+            return Navigation::Pop;
+        }
+        Navigation::None
     }
     fn draw(&mut self, _batch: &mut DrawBatch) {
         clear_background(BLACK);
-        let screen_w = screen_width();
-        let _screen_h = screen_height();
-        let center_x = screen_w / 2.0 - 180.0;
-        let mut y = 120.0;
-        let line_height = 50.0;
-        draw_text("Creating new world", center_x, y, 36.0, WHITE);
-        y += line_height * 2.0;
-        draw_text("World Name:", center_x, y, 28.0, WHITE);
-        let color = if self.selected_field == 0 { YELLOW } else { WHITE };
-        draw_rectangle(center_x + 160.0, y - 28.0, 200.0, 36.0, DARKGRAY);
-        draw_text(&self.world_name, center_x + 170.0, y, 28.0, color);
-        y += line_height;
-        draw_text("Seed:", center_x, y, 28.0, WHITE);
-        let color = if self.selected_field == 1 { YELLOW } else { WHITE };
-        draw_rectangle(center_x + 160.0, y - 28.0, 200.0, 36.0, DARKGRAY);
-        draw_text(&self.seed_input, center_x + 170.0, y, 28.0, color);
-        y += line_height * 1.5;
-        let color = if self.selected_field == 2 { YELLOW } else { WHITE };
-        draw_rectangle_lines(center_x, y, 180.0, 48.0, 2.0, color);
-        draw_text("Create", center_x + 30.0, y + 36.0, 32.0, color);
-        let color = if self.selected_field == 3 { YELLOW } else { WHITE };
-        draw_rectangle_lines(center_x + 220.0, y, 140.0, 48.0, 2.0, color);
-        draw_text("Back", center_x + 240.0, y + 36.0, 32.0, color);
-        
+        self.layout();
+        let center_x = screen_width() / 2.0 - 210.0;
+        self.title.draw(center_x, 120.0);
+        self.name_label.draw(center_x, self.name_input.rect.y + 28.0);
+        self.name_input.draw();
+        self.seed_label.draw(center_x, self.seed_input.rect.y + 28.0);
+        self.seed_input.draw();
+        self.mode_label.draw(center_x, self.mode.rect.y + 28.0);
+        self.mode.draw();
+        self.create.draw();
+        self.back.draw();
+
         if let Some(msg) = &self.error_message {
-            draw_text(msg, center_x, y + 80.0, 24.0, RED);
+            msg.draw(center_x, self.create.rect.y + 80.0);
         }
     }
-    fn name(&self) -> &str { "createworld" }
-} 
\ No newline at end of file
+} 
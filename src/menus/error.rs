@@ -0,0 +1,102 @@
+use gaymwtf_core::DrawBatch;
+use crate::menus::navigation::{Navigation, Route, Screen};
+use crate::mods::MissingModsError;
+use macroquad::prelude::*;
+use crate::ui::{split_row, style, Button, FocusRing, Label};
+
+pub struct ErrorMenu {
+    title: Label,
+    lines: Vec<Label>,
+    world_name: Option<String>,
+    repair: Option<Button>,
+    back: Button,
+    focus: FocusRing,
+}
+
+impl ErrorMenu {
+    pub fn new(title: &str, error: &anyhow::Error) -> Self {
+        let message = format!("{:#}", error);
+        Self {
+            title: Label::new(title, 40.0).with_color(style::ERROR_COLOR),
+            lines: wrap_text(&message, 60).iter().map(|l| Label::new(l, 26.0)).collect(),
+            world_name: None,
+            repair: None,
+            back: Button::new("Back"),
+            focus: FocusRing::new(1).with_arrows(),
+        }
+    }
+
+    /// Error screen for a world that failed to load, offering to repair it.
+    pub fn for_world(world_name: &str, error: &anyhow::Error) -> Self {
+        let mut menu = Self::new(&format!("Failed to load world \"{}\"", world_name), error);
+        menu.world_name = Some(world_name.to_string());
+        if !error.chain().any(|e| e.is::<MissingModsError>()) {
+            menu.repair = Some(Button::new("Repair world"));
+        }
+        menu
+    }
+
+    fn buttons(&self) -> Vec<&Button> {
+        self.repair.iter().chain(std::iter::once(&self.back)).collect()
+    }
+
+    fn layout(&mut self) {
+        let count = if self.repair.is_some() { 2 } else { 1 };
+        let total_w = count as f32 * 260.0 - 20.0;
+        let row = Rect::new(screen_width() / 2.0 - total_w / 2.0, screen_height() - 140.0, total_w, 50.0);
+        let rects = split_row(row, count, 20.0);
+        if let Some(repair) = &mut self.repair {
+            repair.rect = rects[0];
+        }
+        self.back.rect = rects[count - 1];
+    }
+}
+
+fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + word.chars().count() + 1 > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+impl Screen for ErrorMenu {
+    fn update(&mut self, _dt: f32) -> Navigation {
+        self.layout();
+        let rects: Vec<Rect> = self.buttons().iter().map(|b| b.rect).collect();
+        self.focus.update(&rects);
+        let mut index = 0;
+        if let (Some(repair), Some(name)) = (&mut self.repair, &self.world_name) {
+            if repair.update(self.focus.is_focused(0)) {
+                return Navigation::Replace(Route::RepairWorld { world: name.clone() });
+            }
+            index += 1;
+        }
+        if self.back.update(self.focus.is_focused(index)) || is_key_pressed(KeyCode::Escape) {
+            return Navigation::Pop;
+        }
+        Navigation::None
+    }
+
+    fn draw(&mut self, _batch: &mut DrawBatch) {
+        clear_background(BLACK);
+        self.layout();
+        self.title.draw(60.0, 80.0);
+        for (i, line) in self.lines.iter().enumerate() {
+            line.draw(60.0, 140.0 + i as f32 * 30.0);
+        }
+        for button in self.buttons() {
+            button.draw();
+        }
+    }
+}
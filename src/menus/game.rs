@@ -1,64 +1,98 @@
 use gaymwtf_core::{
-    Menu, MenuAction, DrawBatch, TileRegistry, ObjectRegistry, 
+    DrawBatch, Object, TileRegistry, ObjectRegistry, 
     BiomeRegistry, World, CHUNK_PIXELS, TILE_SIZE
 };
 use macroquad::prelude::*;
 use macroquad::text::draw_text_ex;
 use macroquad::text::TextParams;
-use crate::utils::system::SystemInfo;
+use crate::console::{CommandContext, CommandRegistry, Console};
+use crate::assets;
+use crate::content::{self, Manifest};
+use crate::hud::{BiomeOverlay, DebugContext, DebugOverlay, MapCache, Minimap, WorldMap};
+use crate::objects::DataObject;
 use crate::player::{Player, PlayerTextures};
-use crate::menus::pause::PauseMenu;
-use std::fs;
-use serde::{Serialize, Deserialize};
+use crate::scripting::{self, Scripts};
+use crate::menus::navigation::{Navigation, PauseSettings, Route, Screen, SharedPauseSettings};
+use crate::utils::metadata::{Waypoint, WorldMetadata};
+use crate::utils::saves::{self, THUMBNAIL_FILE};
+use crate::worldgen::preview::save_thumbnail;
+use crate::worldgen::spawn::{spawn_chunk, spawn_position};
+use crate::worldgen::{generate_chunk, WorldGenerator};
+use std::any::Any;
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use anyhow::Context;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-pub struct WorldGenInfo {
-    pub seed: u32,
-}
-
-impl WorldGenInfo {
-    pub fn load(path: &str) -> anyhow::Result<Self> {
-        let data = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&data)?)
-    }
-}
+/// Chunks rendered into the minimap cache per frame at most.
+const CHUNKS_PER_FRAME: usize = 4;
+/// Map textures of chunks farther than this from the player, in chunks, are
+/// dropped; the world map renders them again when it shows them.
+const MAP_CACHE_RADIUS: i32 = 32;
+/// How far from the player, center to center, objects can be interacted with.
+const INTERACT_RANGE: f32 = TILE_SIZE * 2.0;
+/// How long a notice stays at the top of the screen, in seconds.
+const NOTICE_SECONDS: f64 = 6.0;
 
 pub struct GameMenu {
     world: World,
     camera: Camera2D,
-    paused: bool,
-    pause_menu: PauseMenu,
+    pause_settings: SharedPauseSettings,
     world_name: String,
-    seed: u32,
-    system_info: SystemInfo,
+    metadata: WorldMetadata,
+    generator: WorldGenerator,
+    debug: DebugOverlay,
+    biome_overlay: BiomeOverlay,
+    map_cache: MapCache,
+    minimap: Minimap,
+    world_map: WorldMap,
+    console: Console,
+    commands: CommandRegistry,
+    /// This world's scripts; a new world starts with none.
+    scripts: Scripts,
+    /// Text shown at the top of the screen and the time it disappears.
+    notice: Option<(String, f64)>,
 }
 
 impl GameMenu {
     pub async fn new(world_name: &str) -> anyhow::Result<Self> {
+        let mut metadata = WorldMetadata::load(&saves::world_dir(world_name))?;
+        let content = crate::mods::content_for_world(&metadata)?;
         let mut tile_registry = TileRegistry::new();
-        crate::register_tiles(&mut tile_registry).await?;
+        crate::register_tiles(&mut tile_registry, &content.manifest).await?;
         let mut object_registry = ObjectRegistry::new();
-        crate::register_objects(&mut object_registry).await?;
-        let player_textures = PlayerTextures::new()?;
+        let mut scripts = Scripts::new();
+        crate::register_objects(&mut object_registry, &content.manifest, &mut scripts).await?;
+        let player_textures = PlayerTextures::new();
         let player_pos = vec2(TILE_SIZE * 5.0, TILE_SIZE * 5.0);
         object_registry.register(Player::new(player_pos, player_textures));
         let mut biome_registry = BiomeRegistry::new();
-        crate::register_biomes(&mut biome_registry).await?;
+        crate::register_biomes(&mut biome_registry, &content).await?;
+        assets::load_all_and_report();
         let mut world = World::load_world(&format!("saves/{}", world_name), tile_registry, object_registry, biome_registry)
-            .map_err(|e| anyhow::anyhow!(e))?;
-            
-        let worldgen_path = format!("saves/{}/gamestate.json", world_name);
-        let seed = WorldGenInfo::load(&worldgen_path)?.seed;
+            .map_err(|e| anyhow::anyhow!(e))
+            .with_context(|| format!("Failed to read world data of '{}'", world_name))?;
+        content::activate(&content);
 
-        let mut player_pos = Vec2::ZERO;
+        if metadata.name.is_empty() {
+            metadata.name = saves::read_world_name(&saves::world_dir(world_name)).unwrap_or_else(|| world_name.to_string());
+        }
+        let generator = metadata.generator();
+        metadata.explored.extend(world.chunks.keys().copied());
+
+        let mut player_pos = None;
         'outer: for chunk in world.chunks.values() {
             for object in &chunk.objects {
                 if object.get_type_tag() == "player" {
-                    player_pos = object.get_pos();
+                    player_pos = Some(object.get_pos());
                     break 'outer;
                 }
             }
         }
+        let player_pos = match player_pos {
+            Some(pos) => pos,
+            None => Self::respawn(&mut world, &mut metadata, &generator, &content.manifest).await?,
+        };
         let camera = Camera2D {
             target: player_pos,
             zoom: Vec2::ZERO,
@@ -69,161 +103,327 @@ impl GameMenu {
         Ok(Self {
             world,
             camera,
-            paused: false,
-            pause_menu: PauseMenu::new(),
+            pause_settings: Rc::new(RefCell::new(PauseSettings::default())),
             world_name: world_name.to_string(),
-            seed,
-            system_info: SystemInfo::new(),
+            generator,
+            metadata,
+            debug: DebugOverlay::new(),
+            biome_overlay: BiomeOverlay::new(),
+            map_cache: MapCache::new(),
+            minimap: Minimap::new(),
+            world_map: WorldMap::new(),
+            console: Console::new(),
+            commands: CommandRegistry::new(),
+            scripts,
+            notice: None,
         })
     }
 }
 
-impl Menu for GameMenu {
-    fn update(&mut self, _dt: f32) -> MenuAction {
-        self.system_info.update();
-        
-        if is_key_pressed(KeyCode::F3) {
-            self.system_info.toggle_debug();
+impl GameMenu {
+    /// Shows `text` at the top of the screen for a few seconds and keeps it
+    /// in the console log.
+    pub fn show_notice(&mut self, text: &str) {
+        self.console.print(text, WHITE);
+        self.notice = Some((text.to_string(), get_time() + NOTICE_SECONDS));
+    }
+
+    fn draw_notice(&mut self) {
+        let Some((text, until)) = &self.notice else { return };
+        if get_time() > *until {
+            self.notice = None;
+            return;
+        }
+        let size = measure_text(text, None, 24, 1.0);
+        let x = (screen_width() - size.width) / 2.0;
+        draw_rectangle(x - 8.0, 8.0, size.width + 16.0, size.height + 16.0, Color::new(0.0, 0.0, 0.0, 0.6));
+        draw_text(text, x, 16.0 + size.offset_y, 24.0, WHITE);
+    }
+
+    /// Puts a new player on the world's spawn tile, generating its chunk if needed.
+    async fn respawn(
+        world: &mut World,
+        metadata: &mut WorldMetadata,
+        generator: &WorldGenerator,
+        manifest: &Manifest,
+    ) -> anyhow::Result<Vec2> {
+        let spawn = metadata.spawn_point(&world.biome_registry, manifest);
+        let chunk_pos = spawn_chunk(spawn);
+        if !world.chunks.contains_key(&chunk_pos) {
+            let chunk = generate_chunk(chunk_pos, generator, &world.tile_registry, &world.object_registry, &world.biome_registry).await?;
+            world.add_chunk(chunk);
+        }
+        let pos = spawn_position(spawn);
+        if let (Some(mut player), Some(chunk)) = (world.object_registry.create_object_by_id("player"), world.chunks.get_mut(&chunk_pos)) {
+            player.set_pos(pos);
+            chunk.objects.push(player);
+        }
+        Ok(pos)
+    }
+}
+
+impl Screen for GameMenu {
+    fn update(&mut self, _dt: f32) -> Navigation {
+        if self.pause_settings.borrow().exit_requested {
+            return Navigation::Pop;
         }
         let dt = get_frame_time();
+        if self.debug.update(dt) {
+            self.pause_settings.borrow_mut().show_debug = self.debug.is_visible();
+        }
+        if self.debug.combo(KeyCode::B) {
+            self.biome_overlay.cycle();
+        }
         let screen_size = vec2(screen_width(), screen_height());
-        if !self.paused {
-            crate::update_camera(&mut self.camera);
-            let player_objects = self.world.get_objects_by_type("player");
-            let player_pos: Vec2 = player_objects.first().map(|e| e.get_pos()).unwrap_or(Vec2::ZERO);
-            self.camera.target = player_pos;
-            let player_chunk_pos = (
-                (player_pos.x / CHUNK_PIXELS).floor() as i32,
-                (player_pos.y / CHUNK_PIXELS).floor() as i32,
+        if self.world_map.is_open() {
+            self.world_map.update(
+                dt,
+                &mut self.map_cache,
+                &self.metadata.explored,
+                &self.generator,
+                &self.world.biome_registry,
+                &mut self.metadata.waypoints,
             );
-            let render_dist = 2;
-            for y in -render_dist..=render_dist {
-                for x in -render_dist..=render_dist {
-                    let chunk_pos_to_check = (player_chunk_pos.0 + x, player_chunk_pos.1 + y);
-                    if !self.world.chunks.contains_key(&chunk_pos_to_check) {
-                        let new_chunk = futures::executor::block_on(
-                            crate::worldgen::generate_chunk(
-                                chunk_pos_to_check,
-                                self.seed,
-                                &self.world.tile_registry,
-                                &self.world.object_registry,
-                                &self.world.biome_registry,
-                            )
-                        ).unwrap();
-                        self.world.add_chunk(new_chunk);
-                    }
+            return Navigation::None;
+        }
+        if self.console.is_open() {
+            if let Some(line) = self.console.update(&self.commands) {
+                let mut ctx = CommandContext {
+                    world: &mut self.world,
+                    metadata: &mut self.metadata,
+                    generator: &self.generator,
+                    save_requested: false,
+                    changed_chunks: Vec::new(),
+                };
+                self.console.run(&self.commands, &mut ctx, &line);
+                let (save_requested, changed_chunks) = (ctx.save_requested, ctx.changed_chunks);
+                for chunk_pos in changed_chunks {
+                    self.map_cache.invalidate(chunk_pos);
                 }
-            }
-            self.world.update(self.camera.target, screen_size);
-            if is_key_pressed(KeyCode::Escape) {
-                self.paused = true;
-            }
-        } else {
-            let action = self.pause_menu.update(dt);
-            match action {
-                MenuAction::ChangeState(ref state) if state == "save" => {
-                    self.world.save_world(&format!("saves/{}", self.world_name)).ok();
-                    self.paused = false;
+                if save_requested {
+                    self.save();
                 }
-                MenuAction::ChangeState(ref state) if state == "exit" => {
-                    self.world.save_world(&format!("saves/{}", self.world_name)).ok();
-                    return MenuAction::ChangeState("menu".to_string());
+            }
+            return Navigation::None;
+        }
+        if is_key_pressed(KeyCode::GraveAccent) {
+            self.console.toggle();
+            return Navigation::None;
+        }
+        if is_key_pressed(KeyCode::M) {
+            self.world_map.open(self.player_pos() / TILE_SIZE + 0.5);
+            return Navigation::None;
+        }
+        // Both clocks stop while the map or console has the game on hold.
+        self.metadata.play_time += dt as f64;
+        self.metadata.world_time += dt as f64;
+        crate::update_camera(&mut self.camera);
+        self.camera.zoom *= self.pause_settings.borrow().zoom;
+        let player_objects = self.world.get_objects_by_type("player");
+        let player_pos: Vec2 = player_objects.first().map(|e| e.get_pos()).unwrap_or(Vec2::ZERO);
+        self.camera.target = player_pos;
+        let player_chunk_pos = (
+            (player_pos.x / CHUNK_PIXELS).floor() as i32,
+            (player_pos.y / CHUNK_PIXELS).floor() as i32,
+        );
+        let render_dist = 2;
+        for y in -render_dist..=render_dist {
+            for x in -render_dist..=render_dist {
+                let chunk_pos_to_check = (player_chunk_pos.0 + x, player_chunk_pos.1 + y);
+                if !self.world.chunks.contains_key(&chunk_pos_to_check) {
+                    let started = get_time();
+                    let new_chunk = futures::executor::block_on(
+                        generate_chunk(
+                            chunk_pos_to_check,
+                            &self.generator,
+                            &self.world.tile_registry,
+                            &self.world.object_registry,
+                            &self.world.biome_registry,
+                        )
+                    ).unwrap();
+                    self.debug.record_chunk_time((get_time() - started) as f32);
+                    self.world.add_chunk(new_chunk);
                 }
-                _ => {}
+                self.metadata.explored.insert(chunk_pos_to_check);
             }
         }
-        MenuAction::None
+        self.world.update(self.camera.target, screen_size);
+        let nearby: Vec<(i32, i32)> = (-render_dist..=render_dist)
+            .flat_map(|y| (-render_dist..=render_dist).map(move |x| (player_chunk_pos.0 + x, player_chunk_pos.1 + y)))
+            .collect();
+        scripting::tick_objects(&mut self.world, &nearby, dt);
+        self.add_script_spawns();
+        for line in self.scripts.take_printed() {
+            self.console.print(&line, WHITE);
+        }
+        self.minimap.update();
+        self.map_cache.build_missing(
+            self.world.chunks.keys().copied(),
+            &self.generator,
+            &self.world.biome_registry,
+            CHUNKS_PER_FRAME,
+        );
+        self.map_cache.retain_near(player_chunk_pos, MAP_CACHE_RADIUS);
+        if is_key_pressed(KeyCode::N) {
+            self.add_waypoint();
+        }
+        if is_key_pressed(KeyCode::E) {
+            self.interact();
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            self.save();
+            return Navigation::Push(Route::Pause {
+                settings: self.pause_settings.clone(),
+                seed: self.metadata.seed_label(),
+            });
+        }
+        Navigation::None
     }
     fn draw(&mut self, _batch: &mut DrawBatch) {
         let screen_size = vec2(screen_width(), screen_height());
-        if !self.paused {
-            clear_background(BLACK);
-            set_camera(&self.camera);
-            self.world.draw(self.camera.target, screen_size);
-            
-            if self.system_info.is_debug_visible() {
-                self.draw_chunk_borders();
-            }
-            
-            set_default_camera();
-            
-            if self.system_info.is_debug_visible() {
-                self.draw_debug_info();
-            }
-        } else {
-            self.pause_menu.draw(&mut DrawBatch::new());
+        clear_background(BLACK);
+        set_camera(&self.camera);
+        self.world.draw(self.camera.target, screen_size);
+        self.biome_overlay.draw_world(&self.camera, &self.generator, &self.world.biome_registry);
+
+        if self.debug.is_visible() {
+            self.draw_chunk_borders();
+        }
+        
+        set_default_camera();
+
+        let player_tile = self.player_pos() / TILE_SIZE + 0.5;
+        if self.world_map.is_open() {
+            self.world_map.draw(
+                &self.map_cache,
+                &self.metadata.explored,
+                player_tile,
+                self.player_facing(),
+                &self.metadata.waypoints,
+            );
+            return;
+        }
+        self.biome_overlay.draw_legend();
+        self.minimap.draw(
+            &self.map_cache,
+            self.world.chunks.keys().copied(),
+            player_tile,
+            self.player_facing(),
+            &self.metadata.waypoints,
+        );
+
+        if self.debug.is_visible() {
+            let player = self.world.get_objects_by_type("player").first().map(|p| p.get_pos());
+            self.debug.draw(&DebugContext {
+                world: &self.world,
+                generator: &self.generator,
+                camera: &self.camera,
+                player,
+                seed: self.metadata.seed_label(),
+            });
+        }
+        self.draw_notice();
+        if self.console.is_open() {
+            self.console.draw();
         }
     }
-    fn name(&self) -> &str { "game" }
+
+    /// Applies whatever the pause overlay changed while it was open.
+    fn resume(&mut self) {
+        self.debug.set_visible(self.pause_settings.borrow().show_debug);
+    }
 } 
 
 impl GameMenu {
+    fn player_pos(&self) -> Vec2 {
+        self.world.get_objects_by_type("player").first().map_or(Vec2::ZERO, |p| p.get_pos())
+    }
 
-    fn draw_debug_info(&self) {
-        let x = 10.0;
-        let mut y = 20.0;
-        let line_height = 22.0;
-        let font_size = 20.0;
-        
-        let fps = self.system_info.fps;
-        let cpu = self.system_info.cpu_usage;
-        let mem = self.system_info.process_memory;
-        
-        let (fps_value, fps_color) = if fps >= 45 {
-            (format!("{}", fps), GREEN)
-        } else if fps >= 30 {
-            (format!("{}", fps), YELLOW)
-        } else {
-            (format!("{}", fps), RED)
-        };
-        
-        let (cpu_value, cpu_color) = if cpu < 10.0 {
-            (format!("{:.1}%", cpu), GREEN)
-        } else if cpu < 15.0 {
-            (format!("{:.1}%", cpu), YELLOW)
-        } else {
-            (format!("{:.1}%", cpu), RED)
-        };
-        
-        let (mem_value, mem_unit, mem_color) = if mem < 128 {
-            (format!("{}", mem), "MB".to_string(), GREEN)
-        } else if mem < 256 {
-            (format!("{:.1}", mem as f32 / 1024.0), "GB".to_string(), YELLOW)
-        } else {
-            (format!("{:.1}", mem as f32 / 1024.0), "GB".to_string(), RED)
-        };
+    fn player_facing(&self) -> Vec2 {
+        self.world
+            .get_objects_by_type("player")
+            .first()
+            .and_then(|object| {
+                let object: &dyn Any = &***object;
+                object.downcast_ref::<Player>()
+            })
+            .map_or(vec2(0.0, 1.0), Player::facing)
+    }
 
+    /// Marks the tile the player stands on.
+    fn add_waypoint(&mut self) {
+        let tile = (self.player_pos() / TILE_SIZE + 0.5).floor();
+        let name = format!("Waypoint {}", self.metadata.waypoints.len() + 1);
+        self.metadata.waypoints.push(Waypoint { name, x: tile.x as i32, y: tile.y as i32 });
+    }
 
-        draw_rectangle(5.0, 5.0, 200.0, 140.0, Color::new(0.0, 0.0, 0.0, 0.3));
-        
-        draw_text("FPS: ", x, y, font_size, WHITE);
-        draw_text(&fps_value, x + 50.0, y, font_size, fps_color);
-        y += line_height;
-        
-        draw_text("CPU: ", x, y, font_size, WHITE);
-        draw_text(&cpu_value, x + 50.0, y, font_size, cpu_color);
-        y += line_height;
-        
-        draw_text("MEM: ", x, y, font_size, WHITE);
-        let mem_text = format!("{} {}", mem_value, mem_unit);
-        draw_text(&mem_text, x + 70.0, y, font_size, mem_color);
-        y += line_height;
-        
-        if let Some(player) = self.world.get_objects_by_type("player").first() {
-            let pos = player.get_pos();
-            draw_text("POS: ", x, y, font_size, WHITE);
-            draw_text(&format!("{:.1}, {:.1}", pos.x / TILE_SIZE as f32, pos.y / TILE_SIZE as f32), x + 60.0, y, font_size, WHITE);
-            y += line_height;
-            
-            let chunk_x = (pos.x / CHUNK_PIXELS as f32).floor() as i32;
-            let chunk_y = (pos.y / CHUNK_PIXELS as f32).floor() as i32;
-            draw_text("CHUNK: ", x, y, font_size, WHITE);
-            draw_text(&format!("{}:{}", chunk_x, chunk_y), x + 90.0, y, font_size, WHITE);
+    /// Places what scripts spawned this frame. Only manifest objects can be
+    /// spawned, and only into loaded chunks.
+    fn add_script_spawns(&mut self) {
+        for (id, pos) in self.scripts.take_spawns() {
+            if !content::content().is_ok_and(|c| c.object(&id).is_some()) {
+                eprintln!("A script tried to spawn unknown object '{}'", id);
+                continue;
+            }
+            let chunk_pos = ((pos.x / CHUNK_PIXELS).floor() as i32, (pos.y / CHUNK_PIXELS).floor() as i32);
+            let (Some(chunk), Some(mut object)) =
+                (self.world.chunks.get_mut(&chunk_pos), self.world.object_registry.create_object_by_id(&id))
+            else {
+                continue;
+            };
+            object.set_pos(pos);
+            chunk.objects.push(object);
+            self.map_cache.invalidate(chunk_pos);
+        }
+    }
+
+    /// Interacts with the nearest object in reach whose script handles it.
+    fn interact(&mut self) {
+        let Some((player_chunk, index)) = self.world.chunks.iter().find_map(|(pos, chunk)| {
+            chunk.objects.iter().position(|o| o.get_type_tag() == "player").map(|i| (*pos, i))
+        }) else {
+            return;
+        };
+        let mut player = self.world.chunks.get_mut(&player_chunk).unwrap().objects.remove(index);
+        let center = |object: &dyn Object| object.get_pos() + object.get_size() / 2.0;
+        let player_center = center(&*player);
+        let target = self
+            .world
+            .chunks
+            .values_mut()
+            .flat_map(|chunk| chunk.objects.iter_mut())
+            .filter_map(|object| {
+                let distance = center(&**object).distance(player_center);
+                let object: &mut dyn Any = &mut **object;
+                object.downcast_mut::<DataObject>().filter(|o| o.has_handler("interact")).map(|o| (o, distance))
+            })
+            .filter(|(_, distance)| *distance <= INTERACT_RANGE)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((target, _)) = target {
+            target.interact(&mut *player);
         }
+        self.world.chunks.get_mut(&player_chunk).unwrap().objects.push(player);
     }
-    
+
+    fn save(&mut self) {
+        let save_dir = format!("saves/{}", self.world_name);
+        if let Err(e) = self.world.save_world(&save_dir) {
+            eprintln!("Failed to save world chunks: {}", e);
+        }
+        self.metadata.last_played = saves::now_secs();
+        if let Err(e) = self.metadata.save(Path::new(&save_dir)) {
+            eprintln!("Failed to save world metadata: {:?}", e);
+        }
+        let player_pos = self.player_pos();
+        let center = ((player_pos.x / TILE_SIZE) as i32, (player_pos.y / TILE_SIZE) as i32);
+        let thumbnail_path = Path::new(&save_dir).join(THUMBNAIL_FILE);
+        if let Err(e) = save_thumbnail(&self.generator, &self.world.biome_registry, center, &thumbnail_path) {
+            eprintln!("Failed to save world thumbnail: {:?}", e);
+        }
+    }
+
     fn draw_chunk_borders(&self) {
-        let chunk_size = CHUNK_PIXELS as f32;
+        let chunk_size = CHUNK_PIXELS;
         let camera_pos = self.camera.target;
         let screen_size = vec2(screen_width(), screen_height());
         
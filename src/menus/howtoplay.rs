@@ -1,59 +1,63 @@
-use gaymwtf_core::{Menu, MenuAction, DrawBatch};
+use gaymwtf_core::DrawBatch;
+use crate::menus::navigation::{Navigation, Screen};
 use macroquad::prelude::*;
+use crate::ui::{Button, FocusRing, Label};
 
 pub struct HowToPlayMenu {
-    back_hovered: bool,
+    lines: Vec<Label>,
+    back: Button,
+    focus: FocusRing,
+}
+
+impl Default for HowToPlayMenu {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HowToPlayMenu {
     pub fn new() -> Self {
-        Self { back_hovered: false }
+        let lines = [
+            "Controls:",
+            "WASD - movement",
+            "E - interact",
+            "M - world map",
+            "N - mark a waypoint",
+            "+ / - - minimap zoom",
+            "F3 - debug, F3+1..5 - debug sections",
+            "F3+B - biome and noise overlay",
+            "` - developer console",
+            "ESC - pause",
+        ];
+        Self {
+            lines: lines.iter().map(|l| Label::new(l, 32.0)).collect(),
+            back: Button::new("Back"),
+            focus: FocusRing::new(1),
+        }
     }
-    fn back_rect(&self, screen_w: f32, screen_h: f32) -> Rect {
-        Rect::new(screen_w / 2.0 - 80.0, screen_h - 120.0, 160.0, 50.0)
+
+    fn layout(&mut self) {
+        self.back.rect = Rect::new(screen_width() / 2.0 - 80.0, screen_height() - 120.0, 160.0, 50.0);
     }
 }
 
-impl Menu for HowToPlayMenu {
-    fn update(&mut self, _dt: f32) -> MenuAction {
-        let (mx, my) = mouse_position();
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-        self.back_hovered = self.back_rect(screen_w, screen_h).contains(vec2(mx, my));
-        if self.back_hovered && is_mouse_button_pressed(MouseButton::Left) {
-            return MenuAction::ChangeState("start".to_string());
+impl Screen for HowToPlayMenu {
+    fn update(&mut self, _dt: f32) -> Navigation {
+        self.layout();
+        self.focus.update(&[self.back.rect]);
+        if self.back.update(self.focus.is_focused(0)) || is_key_pressed(KeyCode::Escape) {
+            return Navigation::Pop;
         }
-        MenuAction::None
+        Navigation::None
     }
     fn draw(&mut self, _batch: &mut DrawBatch) {
         clear_background(BLACK);
-        let screen_w = screen_width();
-        let _screen_h = screen_height();
-        let lines = [
-            "Controls:",
-            "WASD - movement",
-            "F3 - debug",
-            "ESC - pause",
-        ];
+        self.layout();
         let start_y = 120.0;
         let line_height = 40.0;
-        for (i, line) in lines.iter().enumerate() {
-            draw_text(
-                line,
-                screen_w / 2.0 - 200.0,
-                start_y + i as f32 * line_height,
-                32.0,
-                WHITE,
-            );
+        for (i, line) in self.lines.iter().enumerate() {
+            line.draw(screen_width() / 2.0 - 200.0, start_y + i as f32 * line_height);
         }
-        let color = if self.back_hovered { YELLOW } else { WHITE };
-        draw_text(
-            "Back",
-            screen_w / 2.0 - 60.0,
-            _screen_h - 90.0,
-            36.0,
-            color,
-        );
+        self.back.draw();
     }
-    fn name(&self) -> &str { "howtoplay" }
-} 
\ No newline at end of file
+} 
@@ -4,4 +4,7 @@ pub mod about;
 pub mod worlds;
 pub mod createworld;
 pub mod pause;
-pub mod game;
\ No newline at end of file
+pub mod packs;
+pub mod game;
+pub mod error;
+pub mod navigation;
\ No newline at end of file
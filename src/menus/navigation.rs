@@ -0,0 +1,110 @@
+use gaymwtf_core::DrawBatch;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Settings shared between a running game and its pause overlay.
+#[derive(Debug, Clone, Copy)]
+pub struct PauseSettings {
+    pub zoom: f32,
+    pub show_debug: bool,
+    pub exit_requested: bool,
+}
+
+impl Default for PauseSettings {
+    fn default() -> Self {
+        Self { zoom: 1.0, show_debug: false, exit_requested: false }
+    }
+}
+
+pub type SharedPauseSettings = Rc<RefCell<PauseSettings>>;
+
+/// Screens that can be opened. Payloads travel as fields, not encoded strings.
+#[derive(Debug, Clone)]
+pub enum Route {
+    Start,
+    HowToPlay,
+    About,
+    ResourcePacks,
+    Worlds,
+    CreateWorld,
+    Game { world: String },
+    RepairWorld { world: String },
+    Pause { settings: SharedPauseSettings, seed: String },
+}
+
+/// What a screen asks the stack to do after an update.
+#[derive(Debug, Clone)]
+pub enum Navigation {
+    None,
+    /// Opens a screen on top of the current one.
+    Push(Route),
+    /// Swaps the current screen for another, keeping the rest of the stack.
+    Replace(Route),
+    /// Closes the current screen and returns to the one below it.
+    Pop,
+    Quit,
+}
+
+pub trait Screen {
+    fn update(&mut self, dt: f32) -> Navigation;
+    fn draw(&mut self, batch: &mut DrawBatch);
+
+    /// Overlays are drawn on top of the screen below them instead of hiding it.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    /// Called when the screen becomes the top of the stack again.
+    fn resume(&mut self) {}
+}
+
+/// A stack of screens; only the top one receives updates.
+pub struct ScreenStack {
+    screens: Vec<Box<dyn Screen>>,
+}
+
+impl ScreenStack {
+    pub fn new(root: Box<dyn Screen>) -> Self {
+        Self { screens: vec![root] }
+    }
+
+    pub fn push(&mut self, screen: Box<dyn Screen>) {
+        self.screens.push(screen);
+    }
+
+    pub fn replace(&mut self, screen: Box<dyn Screen>) {
+        self.screens.pop();
+        self.screens.push(screen);
+    }
+
+    /// Pops the top screen. Returns false once the stack is empty.
+    pub fn pop(&mut self) -> bool {
+        self.screens.pop();
+        match self.screens.last_mut() {
+            Some(top) => {
+                top.resume();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) -> Navigation {
+        match self.screens.last_mut() {
+            Some(top) => top.update(dt),
+            None => Navigation::Quit,
+        }
+    }
+
+    /// Draws the top screen and, below overlays, every screen down to the first opaque one.
+    pub fn draw(&mut self, batch: &mut DrawBatch) {
+        let first = self
+            .screens
+            .iter()
+            .rposition(|screen| !screen.is_overlay())
+            .unwrap_or(0);
+        for screen in &mut self.screens[first..] {
+            screen.draw(batch);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use gaymwtf_core::DrawBatch;
+use macroquad::prelude::*;
+use std::path::Path;
+use crate::menus::navigation::{Navigation, Screen};
+use crate::packs::{self, PackInfo, PACKS_DIR};
+use crate::ui::{split_row, style, Button, FocusRing, ItemState, Label, ListEvent, ScrollList};
+use crate::utils::settings::Settings;
+
+const ACTIONS: [&str; 3] = ["Enable", "Up", "Down"];
+
+/// Turns resource packs on and off and orders them. Every change is saved
+/// and applied at once.
+pub struct ResourcePacksMenu {
+    settings: Settings,
+    /// Enabled packs in priority order, then the others by name.
+    packs: Vec<PackInfo>,
+    list: ScrollList,
+    title: Label,
+    actions: Vec<Button>,
+    reload: Button,
+    back: Button,
+    focus: FocusRing,
+    message: Option<(String, Color)>,
+}
+
+impl Default for ResourcePacksMenu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResourcePacksMenu {
+    pub fn new() -> Self {
+        let mut menu = Self {
+            settings: Settings::load(),
+            packs: Vec::new(),
+            list: ScrollList::new(50.0, 10.0),
+            title: Label::new("Resource Packs", 40.0),
+            actions: ACTIONS.iter().map(|a| Button::new(a).with_font_size(22.0)).collect(),
+            reload: Button::new("Reload"),
+            back: Button::new("Back"),
+            focus: FocusRing::new(0),
+            message: None,
+        };
+        menu.refresh(None);
+        menu
+    }
+
+    fn is_enabled(&self, id: &str) -> bool {
+        self.settings.resource_packs.iter().any(|p| p == id)
+    }
+
+    fn refresh(&mut self, select: Option<&str>) {
+        let mut installed = packs::discover(Path::new(PACKS_DIR));
+        installed.sort_by_key(|pack| {
+            let rank = self.settings.resource_packs.iter().position(|id| *id == pack.id);
+            (rank.is_none(), rank)
+        });
+        self.packs = installed;
+        self.list.set_items(self.packs.iter().map(|p| p.name().to_string()).collect());
+        self.list.select(select.and_then(|id| self.packs.iter().position(|p| p.id == id)));
+    }
+
+    fn selected(&self) -> Option<&PackInfo> {
+        self.list.selected().and_then(|i| self.packs.get(i))
+    }
+
+    /// Saves the settings, switches the packs and keeps `id` selected.
+    fn apply(&mut self, id: &str) {
+        self.message = self.settings.save().err().map(|e| (format!("{:#}", e), style::ERROR_COLOR));
+        let missing = packs::activate(&self.settings.resource_packs);
+        if self.message.is_none() && !missing.is_empty() {
+            self.message = Some((format!("Not installed: {}", missing.join(", ")), style::ERROR_COLOR));
+        }
+        self.refresh(Some(id));
+    }
+
+    fn run_action(&mut self, action: usize) {
+        let Some(id) = self.selected().map(|p| p.id.clone()) else { return };
+        let enabled = &mut self.settings.resource_packs;
+        let index = enabled.iter().position(|p| *p == id);
+        match (action, index) {
+            (0, Some(i)) => {
+                enabled.remove(i);
+            }
+            // A newly enabled pack goes on top.
+            (0, None) => enabled.insert(0, id.clone()),
+            (1, Some(i)) if i > 0 => enabled.swap(i, i - 1),
+            (2, Some(i)) if i + 1 < enabled.len() => enabled.swap(i, i + 1),
+            _ => return,
+        }
+        self.apply(&id);
+    }
+
+    fn list_rect(&self, screen_w: f32, screen_h: f32) -> Rect {
+        Rect::new(screen_w / 2.0 - 200.0, 80.0, 412.0, screen_h - 300.0)
+    }
+
+    fn layout(&mut self, screen_w: f32, screen_h: f32) {
+        let action_row = Rect::new(screen_w / 2.0 - 200.0, screen_h - 200.0, 400.0, 40.0);
+        for (button, rect) in self.actions.iter_mut().zip(split_row(action_row, ACTIONS.len(), 10.0)) {
+            button.rect = rect;
+        }
+        let bottom_row = Rect::new(screen_w / 2.0 - 170.0, screen_h - 100.0, 340.0, 40.0);
+        let rects = split_row(bottom_row, 2, 20.0);
+        self.reload.rect = rects[0];
+        self.back.rect = rects[1];
+    }
+
+    /// Focusable rects in tab order: the list, then the buttons; the action
+    /// row only exists while a pack is selected.
+    fn focus_rects(&self, screen_w: f32, screen_h: f32) -> Vec<Rect> {
+        let mut rects = vec![self.list_rect(screen_w, screen_h)];
+        if self.list.selected().is_some() {
+            rects.extend(self.actions.iter().map(|b| b.rect));
+        }
+        rects.push(self.reload.rect);
+        rects.push(self.back.rect);
+        rects
+    }
+}
+
+impl Screen for ResourcePacksMenu {
+    fn update(&mut self, _dt: f32) -> Navigation {
+        let screen_w = screen_width();
+        let screen_h = screen_height();
+        self.layout(screen_w, screen_h);
+
+        let rects = self.focus_rects(screen_w, screen_h);
+        self.focus.set_len(rects.len());
+        self.focus.update(&rects);
+
+        let list_focused = matches!(self.focus.focused(), None | Some(0));
+        match self.list.update(self.list_rect(screen_w, screen_h), list_focused) {
+            ListEvent::Activated(_) => self.run_action(0),
+            ListEvent::Selected(_) => self.message = None,
+            ListEvent::None => {}
+        }
+        let enabled = self.selected().is_some_and(|p| self.is_enabled(&p.id));
+        self.actions[0].label = if enabled { "Disable" } else { "Enable" }.to_string();
+
+        let mut index = 1;
+        if self.list.selected().is_some() {
+            for i in 0..self.actions.len() {
+                if self.actions[i].update(self.focus.is_focused(index + i)) {
+                    self.run_action(i);
+                }
+            }
+            index += self.actions.len();
+        }
+        if self.reload.update(self.focus.is_focused(index)) {
+            let selected = self.selected().map(|p| p.id.clone());
+            let missing = packs::activate(&self.settings.resource_packs);
+            self.message = Some(if missing.is_empty() {
+                ("Textures reloaded".to_string(), style::MUTED_COLOR)
+            } else {
+                (format!("Not installed: {}", missing.join(", ")), style::ERROR_COLOR)
+            });
+            self.refresh(selected.as_deref());
+        }
+        if self.back.update(self.focus.is_focused(index + 1)) || is_key_pressed(KeyCode::Escape) {
+            return Navigation::Pop;
+        }
+        Navigation::None
+    }
+
+    fn draw(&mut self, _batch: &mut DrawBatch) {
+        clear_background(BLACK);
+        let screen_w = screen_width();
+        let screen_h = screen_height();
+        self.layout(screen_w, screen_h);
+
+        self.title.draw(screen_w / 2.0 - 200.0, 60.0);
+        self.list.draw(self.list_rect(screen_w, screen_h), |i, rect, state| {
+            let pack = &self.packs[i];
+            let color = match state {
+                ItemState::Selected => style::HOVER_COLOR,
+                ItemState::Hovered => LIGHTGRAY,
+                ItemState::Normal => style::TEXT_COLOR,
+            };
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, color);
+            let rank = self.settings.resource_packs.iter().position(|id| *id == pack.id);
+            let status = rank.map_or("off".to_string(), |r| format!("#{}", r + 1));
+            style::draw_label(&status, rect.x + 8.0, rect.y + 32.0, 22.0, style::MUTED_COLOR);
+            style::draw_label(pack.name(), rect.x + 60.0, rect.y + 22.0, 26.0, color);
+            style::draw_label(&pack.manifest.description, rect.x + 60.0, rect.y + 42.0, 16.0, style::MUTED_COLOR);
+        });
+        if self.packs.is_empty() {
+            let hint = format!("No packs in {}/", PACKS_DIR);
+            style::draw_label(&hint, screen_w / 2.0 - 120.0, 150.0, 28.0, style::MUTED_COLOR);
+        }
+        if self.list.selected().is_some() {
+            for button in &self.actions {
+                button.draw();
+            }
+        }
+        if let Some((msg, color)) = &self.message {
+            style::draw_label(msg, screen_w / 2.0 - 200.0, screen_h - 130.0, 22.0, *color);
+        }
+        style::draw_label(
+            "Packs higher in the list win; files a pack lacks come from assets/",
+            screen_w / 2.0 - 200.0,
+            screen_h - 210.0,
+            16.0,
+            style::MUTED_COLOR,
+        );
+        self.reload.draw();
+        self.back.draw();
+    }
+}
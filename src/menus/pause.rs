@@ -1,72 +1,99 @@
-use gaymwtf_core::{Menu, MenuAction, DrawBatch};
+use gaymwtf_core::DrawBatch;
+use macroquad::miniquad::window::clipboard_set;
 use macroquad::prelude::*;
+use crate::menus::navigation::{Navigation, Route, Screen, SharedPauseSettings};
+use crate::ui::{Button, Checkbox, Column, FocusRing, Label, Slider};
 
 pub struct PauseMenu {
-    hovered: Option<usize>,
-    options: Vec<&'static str>,
+    title: Label,
+    resume: Button,
+    zoom: Slider,
+    show_debug: Checkbox,
+    copy_seed: Button,
+    packs: Button,
+    exit: Button,
+    focus: FocusRing,
+    settings: SharedPauseSettings,
+    seed: String,
 }
 
 impl PauseMenu {
-    pub fn new() -> Self {
+    /// Edits the shared `settings` directly; the game applies them when it resumes.
+    pub fn new(settings: SharedPauseSettings, seed: String) -> Self {
+        let current = *settings.borrow();
         Self {
-            hovered: None,
-            options: vec!["Continue", "Exit to menu"],
+            title: Label::new("Pause", 48.0),
+            resume: Button::new("Continue"),
+            zoom: Slider::new("Zoom", current.zoom, 0.5, 3.0, 0.25),
+            show_debug: Checkbox::new("Debug info", current.show_debug),
+            copy_seed: Button::new("Copy seed"),
+            packs: Button::new("Resource packs"),
+            exit: Button::new("Exit to menu"),
+            focus: FocusRing::new(6).with_arrows(),
+            settings,
+            seed,
         }
     }
-    fn option_rect(&self, i: usize, screen_w: f32, screen_h: f32) -> Rect {
-        let start_y = screen_h / 2.0 - 30.0;
-        let line_height = 60.0;
-        let x = screen_w / 2.0 - 120.0;
-        let y = start_y + i as f32 * line_height - 20.0;
-        Rect::new(x, y, 240.0, 50.0)
+
+    fn layout(&mut self) {
+        let mut column = Column::centered(screen_width() / 2.0, screen_height() / 2.0 - 60.0, 300.0, 14.0);
+        self.resume.rect = column.next(50.0);
+        self.zoom.rect = column.next(30.0);
+        self.show_debug.rect = column.next(30.0);
+        self.copy_seed.rect = column.next(50.0);
+        self.packs.rect = column.next(50.0);
+        self.exit.rect = column.next(50.0);
     }
 }
 
-impl Menu for PauseMenu {
-    fn update(&mut self, _dt: f32) -> MenuAction {
-        let (mx, my) = mouse_position();
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-        self.hovered = None;
-        for (i, _) in self.options.iter().enumerate() {
-            if self.option_rect(i, screen_w, screen_h).contains(vec2(mx, my)) {
-                self.hovered = Some(i);
-                if is_mouse_button_pressed(MouseButton::Left) {
-                    match i {
-                        0 => return MenuAction::ChangeState("save".to_string()),
-                        1 => return MenuAction::ChangeState("exit".to_string()),
-                        _ => {}
-                    }
-                }
-            }
+impl Screen for PauseMenu {
+    fn update(&mut self, _dt: f32) -> Navigation {
+        self.layout();
+        self.focus.update(&[
+            self.resume.rect,
+            self.zoom.rect,
+            self.show_debug.rect,
+            self.copy_seed.rect,
+            self.packs.rect,
+            self.exit.rect,
+        ]);
+        if self.resume.update(self.focus.is_focused(0)) || is_key_pressed(KeyCode::Escape) {
+            return Navigation::Pop;
+        }
+        if self.zoom.update(self.focus.is_focused(1)) {
+            self.settings.borrow_mut().zoom = self.zoom.value();
+        }
+        if self.show_debug.update(self.focus.is_focused(2)) {
+            self.settings.borrow_mut().show_debug = self.show_debug.checked;
+        }
+        if self.copy_seed.update(self.focus.is_focused(3)) {
+            clipboard_set(&self.seed);
+            self.copy_seed.label = "Seed copied".to_string();
         }
-        MenuAction::None
+        if self.packs.update(self.focus.is_focused(4)) {
+            return Navigation::Push(Route::ResourcePacks);
+        }
+        if self.exit.update(self.focus.is_focused(5)) {
+            self.settings.borrow_mut().exit_requested = true;
+            return Navigation::Pop;
+        }
+        Navigation::None
     }
     fn draw(&mut self, _batch: &mut DrawBatch) {
         let screen_w = screen_width();
         let screen_h = screen_height();
+        self.layout();
         draw_rectangle(0.0, 0.0, screen_w, screen_h, Color::new(0.0, 0.0, 0.0, 0.7));
-        draw_text("Pause", screen_w / 2.0 - 60.0, screen_h / 2.0 - 80.0, 48.0, WHITE);
-        let start_y = screen_h / 2.0 - 30.0;
-        let line_height = 60.0;
-        for (i, option) in self.options.iter().enumerate() {
-            let color = if Some(i) == self.hovered { YELLOW } else { WHITE };
-            draw_rectangle_lines(
-                self.option_rect(i, screen_w, screen_h).x,
-                self.option_rect(i, screen_w, screen_h).y,
-                self.option_rect(i, screen_w, screen_h).w,
-                self.option_rect(i, screen_w, screen_h).h,
-                2.0,
-                color,
-            );
-            draw_text(
-                option,
-                screen_w / 2.0 - 80.0,
-                start_y + i as f32 * line_height + 14.0,
-                36.0,
-                color,
-            );
-        }
+        self.title.draw_centered(Rect::new(0.0, screen_h / 2.0 - 140.0, screen_w, 60.0));
+        self.resume.draw();
+        self.zoom.draw();
+        self.show_debug.draw();
+        self.copy_seed.draw();
+        self.packs.draw();
+        self.exit.draw();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
     }
-    fn name(&self) -> &str { "pause" }
-} 
\ No newline at end of file
+} 
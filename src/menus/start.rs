@@ -1,73 +1,65 @@
-use gaymwtf_core::Menu;
-use gaymwtf_core::MenuAction;
 use gaymwtf_core::DrawBatch;
 use macroquad::prelude::*;
+use crate::menus::navigation::{Navigation, Route, Screen};
+use crate::ui::{Button, Column, FocusRing, Label};
 
 pub struct StartMenu {
-    selected: Option<usize>,
-    options: Vec<&'static str>,
+    title: Label,
+    buttons: Vec<Button>,
+    focus: FocusRing,
+}
+
+impl Default for StartMenu {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StartMenu {
     pub fn new() -> Self {
+        let options = ["Start Game", "Resource Packs", "How to Play", "About", "Exit"];
         Self {
-            selected: None,
-            options: vec!["Start Game", "How to Play", "About", "Exit"],
+            title: Label::new("GaymWTF", 64.0),
+            buttons: options.iter().map(|o| Button::new(o)).collect(),
+            focus: FocusRing::new(options.len()).with_arrows(),
         }
     }
 
-    fn option_rect(&self, i: usize, screen_w: f32, screen_h: f32) -> Rect {
-        let start_y = screen_h / 2.0 - 50.0;
-        let line_height = 50.0;
-        let x = screen_w / 2.0 - 100.0;
-        let y = start_y + i as f32 * line_height - 35.0;
-        Rect::new(x, y, 300.0, 50.0)
+    fn layout(&mut self) {
+        let mut column = Column::centered(screen_width() / 2.0, screen_height() / 2.0 - 80.0, 300.0, 12.0);
+        for button in &mut self.buttons {
+            button.rect = column.next(50.0);
+        }
     }
 }
 
-impl Menu for StartMenu {
-    fn update(&mut self, _dt: f32) -> MenuAction {
-        let mouse = mouse_position();
-        let (mx, my) = (mouse.0, mouse.1);
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-        self.selected = None;
-        for (i, _) in self.options.iter().enumerate() {
-            if self.option_rect(i, screen_w, screen_h).contains(vec2(mx, my)) {
-                self.selected = Some(i);
-                if is_mouse_button_pressed(MouseButton::Left) {
-                    match i {
-                        0 => return MenuAction::ChangeState("worlds".to_string()),
-                        1 => return MenuAction::ChangeState("howtoplay".to_string()),
-                        2 => return MenuAction::ChangeState("about".to_string()),
-                        3 => return MenuAction::Quit,
-                        _ => {}
-                    }
+impl Screen for StartMenu {
+    fn update(&mut self, _dt: f32) -> Navigation {
+        self.layout();
+        let rects: Vec<Rect> = self.buttons.iter().map(|b| b.rect).collect();
+        self.focus.update(&rects);
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            if button.update(self.focus.is_focused(i)) {
+                match i {
+                    0 => return Navigation::Push(Route::Worlds),
+                    1 => return Navigation::Push(Route::ResourcePacks),
+                    2 => return Navigation::Push(Route::HowToPlay),
+                    3 => return Navigation::Push(Route::About),
+                    4 => return Navigation::Quit,
+                    _ => {}
                 }
             }
         }
-        MenuAction::None
+        Navigation::None
     }
 
     fn draw(&mut self, _batch: &mut DrawBatch) {
         clear_background(BLACK);
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-        let start_y = screen_h / 2.0 - 50.0;
-        let line_height = 50.0;
-        for (i, option) in self.options.iter().enumerate() {
-            let color = if Some(i) == self.selected { YELLOW } else { WHITE };
-            draw_text(
-                option,
-                screen_w / 2.0 - 100.0,
-                start_y + i as f32 * line_height,
-                40.0,
-                color,
-            );
+        self.layout();
+        let title_rect = Rect::new(0.0, screen_height() / 2.0 - 200.0, screen_width(), 80.0);
+        self.title.draw_centered(title_rect);
+        for button in &self.buttons {
+            button.draw();
         }
     }
-
-    fn name(&self) -> &str {
-        "start"
-    }
-} 
\ No newline at end of file
+} 
@@ -1,136 +1,464 @@
-use gaymwtf_core::{Menu, MenuAction, DrawBatch};
+use gaymwtf_core::{BiomeRegistry, DrawBatch};
 use macroquad::prelude::*;
-use std::fs;
+use std::collections::{HashMap, VecDeque};
+use std::time::SystemTime;
+use crate::menus::navigation::{Navigation, Route, Screen};
+use crate::ui::{split_row, style, Button, FocusRing, ItemState, Label, ListEvent, ScrollList, TextInput};
+use crate::utils::metadata::WorldMetadata;
+use crate::utils::saves::{self, WorldEntry, MAX_WORLD_NAME_LEN, THUMBNAIL_FILE};
+use crate::worldgen::preview::save_thumbnail;
+
+const ACTIONS: [&str; 4] = ["Play", "Rename", "Duplicate", "Delete"];
+/// Thumbnails loaded or rendered per frame, so a long list never stalls the menu.
+const THUMBNAILS_PER_FRAME: usize = 1;
+/// Folder sizes measured per frame; walking a big world's chunks is not free.
+const SIZES_PER_FRAME: usize = 2;
+
+#[derive(PartialEq)]
+enum SortOrder {
+    LastPlayed,
+    Name,
+}
+
+#[derive(PartialEq)]
+enum Mode {
+    Browse,
+    Rename,
+    ConfirmDelete,
+}
 
 pub struct WorldsMenu {
-    worlds: Vec<String>,
-    hovered: Option<usize>,
-    create_hovered: bool,
-    back_hovered: bool,
+    worlds: Vec<WorldEntry>,
+    /// Textures by world folder, with the time their file was written.
+    thumbnails: HashMap<String, (Option<SystemTime>, Texture2D)>,
+    /// Worlds whose thumbnail is missing or older than its file.
+    pending_thumbnails: VecDeque<String>,
+    /// Biomes by a world's mod list, for rendering missing thumbnails; `None`
+    /// when that content failed to load.
+    biome_registries: HashMap<Vec<String>, Option<BiomeRegistry>>,
+    list: ScrollList,
+    title: Label,
+    sort_button: Button,
+    actions: Vec<Button>,
+    create: Button,
+    back: Button,
+    focus: FocusRing,
+    rename_input: TextInput,
+    confirm: Button,
+    cancel: Button,
+    dialog_focus: FocusRing,
+    sort: SortOrder,
+    mode: Mode,
+    error_message: Option<String>,
+}
+
+impl Default for WorldsMenu {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WorldsMenu {
     pub fn new() -> Self {
-        Self {
-            worlds: Self::load_worlds(),
-            hovered: None,
-            create_hovered: false,
-            back_hovered: false,
-        }
-    }
-    fn load_worlds() -> Vec<String> {
-        let mut result = Vec::new();
-        if let Ok(entries) = fs::read_dir("saves") {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    result.push(entry.file_name().to_string_lossy().to_string());
-                }
+        let mut menu = Self {
+            worlds: Vec::new(),
+            thumbnails: HashMap::new(),
+            pending_thumbnails: VecDeque::new(),
+            biome_registries: HashMap::new(),
+            list: ScrollList::new(50.0, 10.0).with_filter(),
+            title: Label::new("My Worlds", 40.0),
+            sort_button: Button::new("Sort: Recent").with_font_size(22.0),
+            actions: ACTIONS.iter().map(|a| Button::new(a).with_font_size(22.0)).collect(),
+            create: Button::new("Create New World").with_font_size(30.0),
+            back: Button::new("Back"),
+            focus: FocusRing::new(0),
+            rename_input: TextInput::new(MAX_WORLD_NAME_LEN),
+            confirm: Button::new("Delete"),
+            cancel: Button::new("Cancel"),
+            dialog_focus: FocusRing::new(3),
+            sort: SortOrder::LastPlayed,
+            mode: Mode::Browse,
+            error_message: None,
+        };
+        menu.reload(None);
+        menu
+    }
+
+    fn reload(&mut self, select: Option<&str>) {
+        self.worlds = saves::list_worlds();
+        if self.sort == SortOrder::Name {
+            self.worlds.sort_by_key(|w| w.name.to_lowercase());
+        }
+        self.thumbnails.retain(|dir, _| self.worlds.iter().any(|w| w.dir_name == *dir));
+        self.pending_thumbnails = self
+            .worlds
+            .iter()
+            .filter(|world| {
+                let cached = self.thumbnails.get(&world.dir_name).map(|(modified, _)| *modified);
+                cached.is_none_or(|modified| modified.is_none() || modified != thumbnail_modified(world))
+            })
+            .map(|world| world.dir_name.clone())
+            .collect();
+        self.list.set_items(self.worlds.iter().map(|w| w.name.clone()).collect());
+        self.list.select(select.and_then(|dir| self.worlds.iter().position(|w| w.dir_name == dir)));
+    }
+
+    /// Loads the next few pending thumbnails; the old texture, if any, stays
+    /// on screen until its replacement is ready.
+    fn load_pending_thumbnails(&mut self) {
+        for _ in 0..THUMBNAILS_PER_FRAME {
+            let Some(dir_name) = self.pending_thumbnails.pop_front() else { return };
+            let Some(world) = self.worlds.iter().find(|w| w.dir_name == dir_name) else { continue };
+            if let Some(texture) = load_thumbnail(world, &mut self.biome_registries) {
+                self.thumbnails.insert(dir_name, (thumbnail_modified(world), texture));
             }
         }
-        result
     }
-    fn world_rect(&self, i: usize, screen_w: f32) -> Rect {
-        Rect::new(screen_w / 2.0 - 200.0, 120.0 + i as f32 * 60.0, 400.0, 50.0)
+
+    /// Measures the selected world first, then the rest of the list a few
+    /// folders per frame.
+    fn measure_pending_sizes(&mut self) {
+        if let Some(world) = self.list.selected().and_then(|i| self.worlds.get_mut(i)) {
+            world.measure_size();
+        }
+        for world in self.worlds.iter_mut().filter(|w| w.size_on_disk.is_none()).take(SIZES_PER_FRAME) {
+            world.measure_size();
+        }
     }
-    fn create_rect(&self, screen_w: f32, screen_h: f32) -> Rect {
-        Rect::new(screen_w / 2.0 - 120.0, screen_h - 180.0, 240.0, 50.0)
+
+    fn selected_world(&self) -> Option<&WorldEntry> {
+        self.list.selected().and_then(|i| self.worlds.get(i))
     }
-    fn back_rect(&self, screen_w: f32, screen_h: f32) -> Rect {
-        Rect::new(screen_w / 2.0 - 80.0, screen_h - 100.0, 160.0, 40.0)
+
+    fn list_rect(&self, screen_w: f32, screen_h: f32) -> Rect {
+        Rect::new(screen_w / 2.0 - 200.0, 80.0, 412.0, screen_h - 400.0)
+    }
+    fn dialog_rect(&self, screen_w: f32, screen_h: f32) -> Rect {
+        Rect::new(screen_w / 2.0 - 250.0, screen_h / 2.0 - 100.0, 500.0, 200.0)
     }
+
+    fn layout(&mut self, screen_w: f32, screen_h: f32) {
+        self.sort_button.rect = Rect::new(screen_w / 2.0 + 110.0, 30.0, 160.0, 36.0);
+        let action_row = Rect::new(screen_w / 2.0 - 200.0, screen_h - 260.0, 400.0, 40.0);
+        for (button, rect) in self.actions.iter_mut().zip(split_row(action_row, ACTIONS.len(), 10.0)) {
+            button.rect = rect;
+        }
+        self.create.rect = Rect::new(screen_w / 2.0 - 140.0, screen_h - 180.0, 280.0, 50.0);
+        self.back.rect = Rect::new(screen_w / 2.0 - 80.0, screen_h - 100.0, 160.0, 40.0);
+
+        let dialog = self.dialog_rect(screen_w, screen_h);
+        self.rename_input.rect = Rect::new(dialog.x + 20.0, dialog.y + 60.0, dialog.w - 40.0, 40.0);
+        let row = Rect::new(dialog.x + 60.0, dialog.y + 130.0, 380.0, 44.0);
+        let rects = split_row(row, 2, 20.0);
+        self.confirm.rect = rects[0];
+        self.cancel.rect = rects[1];
+    }
+
+    /// Focusable rects in tab order: the list with its search first, then the
+    /// buttons; the action row only exists while a world is selected.
+    fn focus_rects(&self, screen_w: f32, screen_h: f32) -> Vec<Rect> {
+        let mut rects = vec![self.list_rect(screen_w, screen_h), self.sort_button.rect];
+        if self.list.selected().is_some() {
+            rects.extend(self.actions.iter().map(|b| b.rect));
+        }
+        rects.push(self.create.rect);
+        rects.push(self.back.rect);
+        rects
+    }
+
+    fn play_selected(&self) -> Navigation {
+        match self.selected_world() {
+            Some(world) => Navigation::Push(Route::Game { world: world.dir_name.clone() }),
+            None => Navigation::None,
+        }
+    }
+
+    fn open_dialog(&mut self, mode: Mode) {
+        let name = self.selected_world().map(|w| w.name.clone()).unwrap_or_default();
+        self.rename_input.set_text(&name);
+        self.confirm.label = if mode == Mode::Rename { "Rename" } else { "Delete" }.to_string();
+        self.dialog_focus.focus(Some(if mode == Mode::Rename { 0 } else { 2 }));
+        self.error_message = None;
+        self.mode = mode;
+    }
+
+    fn run_action(&mut self, action: usize) -> Navigation {
+        let Some(world) = self.selected_world() else {
+            return Navigation::None;
+        };
+        let dir_name = world.dir_name.clone();
+        match action {
+            0 => return self.play_selected(),
+            1 => self.open_dialog(Mode::Rename),
+            2 => match saves::duplicate_world(&dir_name) {
+                Ok(copy) => {
+                    self.error_message = None;
+                    self.reload(Some(&copy));
+                }
+                Err(e) => self.error_message = Some(format!("{:#}", e)),
+            },
+            3 => self.open_dialog(Mode::ConfirmDelete),
+            _ => {}
+        }
+        Navigation::None
+    }
+
+    fn confirm_dialog(&mut self) {
+        let Some((dir_name, old_name)) = self.selected_world().map(|w| (w.dir_name.clone(), w.name.clone())) else {
+            self.mode = Mode::Browse;
+            return;
+        };
+        match self.mode {
+            Mode::Rename => {
+                let new_name = self.rename_input.text().trim().to_string();
+                if new_name != old_name {
+                    if let Err(e) = saves::rename_world(&dir_name, &new_name) {
+                        self.error_message = Some(format!("{:#}", e));
+                        return;
+                    }
+                }
+                self.mode = Mode::Browse;
+                self.reload(Some(&dir_name));
+            }
+            Mode::ConfirmDelete => {
+                if let Err(e) = saves::delete_world(&dir_name) {
+                    self.error_message = Some(format!("{:#}", e));
+                }
+                self.thumbnails.remove(&dir_name);
+                self.mode = Mode::Browse;
+                self.reload(None);
+            }
+            Mode::Browse => {}
+        }
+    }
+
+    fn update_dialog(&mut self) {
+        let renaming = self.mode == Mode::Rename;
+        let input_rect = if renaming { self.rename_input.rect } else { Rect::default() };
+        self.dialog_focus.update(&[input_rect, self.confirm.rect, self.cancel.rect]);
+        if !renaming && self.dialog_focus.is_focused(0) {
+            self.dialog_focus.focus(Some(1));
+        }
+        if renaming && self.rename_input.update(self.dialog_focus.is_focused(0)) {
+            self.error_message = None;
+        }
+        let submit = renaming && self.dialog_focus.is_focused(0) && is_key_pressed(KeyCode::Enter);
+        if self.confirm.update(self.dialog_focus.is_focused(1)) || submit {
+            self.confirm_dialog();
+        } else if self.cancel.update(self.dialog_focus.is_focused(2)) || is_key_pressed(KeyCode::Escape) {
+            self.mode = Mode::Browse;
+            self.error_message = None;
+        }
+    }
+
+    fn draw_dialog(&self, screen_w: f32, screen_h: f32) {
+        draw_rectangle(0.0, 0.0, screen_w, screen_h, Color::new(0.0, 0.0, 0.0, 0.7));
+        let dialog = self.dialog_rect(screen_w, screen_h);
+        draw_rectangle(dialog.x, dialog.y, dialog.w, dialog.h, BLACK);
+        draw_rectangle_lines(dialog.x, dialog.y, dialog.w, dialog.h, 2.0, WHITE);
+        let name = self.selected_world().map_or("", |w| w.name.as_str());
+        if self.mode == Mode::Rename {
+            style::draw_label(&format!("Rename \"{}\" to:", name), dialog.x + 20.0, dialog.y + 40.0, 28.0, style::TEXT_COLOR);
+            self.rename_input.draw();
+        } else {
+            style::draw_label(&format!("Delete \"{}\"?", name), dialog.x + 20.0, dialog.y + 40.0, 28.0, style::TEXT_COLOR);
+            style::draw_label("This cannot be undone.", dialog.x + 20.0, dialog.y + 80.0, 24.0, style::ERROR_COLOR);
+        }
+        self.confirm.draw();
+        self.cancel.draw();
+        if let Some(msg) = &self.error_message {
+            style::draw_label(msg, dialog.x + 20.0, dialog.y + dialog.h + 30.0, 24.0, style::ERROR_COLOR);
+        }
+    }
+}
+
+/// When the world's thumbnail file was last written, if it exists.
+fn thumbnail_modified(world: &WorldEntry) -> Option<SystemTime> {
+    std::fs::metadata(world.path.join(THUMBNAIL_FILE)).and_then(|m| m.modified()).ok()
+}
+
+/// The biomes of the world's own mods, loaded once per mod list.
+fn biome_registry<'a>(
+    registries: &'a mut HashMap<Vec<String>, Option<BiomeRegistry>>,
+    metadata: &WorldMetadata,
+) -> Option<&'a BiomeRegistry> {
+    registries
+        .entry(metadata.mods.clone())
+        .or_insert_with(|| {
+            let content = crate::mods::content_for_world(metadata);
+            content
+                .and_then(|content| crate::init_biome_registry(&content))
+                .map_err(|e| eprintln!("No thumbnails for worlds with mods [{}]: {:#}", metadata.mods.join(", "), e))
+                .ok()
+        })
+        .as_ref()
 }
 
-impl Menu for WorldsMenu {
-    fn update(&mut self, _dt: f32) -> MenuAction {
-        let (mx, my) = mouse_position();
+/// Loads the world's thumbnail, rendering it from the seed with the world's
+/// biomes if the file is missing.
+fn load_thumbnail(world: &WorldEntry, registries: &mut HashMap<Vec<String>, Option<BiomeRegistry>>) -> Option<Texture2D> {
+    let path = world.path.join(THUMBNAIL_FILE);
+    let image = match image::open(&path) {
+        Ok(image) => image.to_rgba8(),
+        Err(_) => {
+            let metadata = world.metadata.as_ref()?;
+            save_thumbnail(&metadata.generator(), biome_registry(registries, metadata)?, (0, 0), &path)
+                .map_err(|e| eprintln!("Failed to create thumbnail for {}: {:?}", world.name, e))
+                .ok()?
+        }
+    };
+    let texture = Texture2D::from_rgba8(image.width() as u16, image.height() as u16, &image);
+    texture.set_filter(FilterMode::Nearest);
+    Some(texture)
+}
+
+impl Screen for WorldsMenu {
+    fn update(&mut self, _dt: f32) -> Navigation {
         let screen_w = screen_width();
         let screen_h = screen_height();
-        self.hovered = None;
-        self.create_hovered = false;
-        self.back_hovered = false;
-
-        for (i, name) in self.worlds.iter().enumerate() {
-            if self.world_rect(i, screen_w).contains(vec2(mx, my)) {
-                self.hovered = Some(i);
-                if is_mouse_button_pressed(MouseButton::Left) {
-                    return MenuAction::ChangeState(format!("play:{}", name));
-                }
-            }
+        self.layout(screen_w, screen_h);
+        self.load_pending_thumbnails();
+        self.measure_pending_sizes();
+
+        if self.mode != Mode::Browse {
+            self.update_dialog();
+            return Navigation::None;
         }
 
-        if self.create_rect(screen_w, screen_h).contains(vec2(mx, my)) {
-            self.create_hovered = true;
-            if is_mouse_button_pressed(MouseButton::Left) {
-                return MenuAction::ChangeState("createworld".to_string());
+        let rects = self.focus_rects(screen_w, screen_h);
+        self.focus.set_len(rects.len());
+        self.focus.update(&rects);
+
+        // The list has the keys until Tab or a click moves focus to a button.
+        let list_focused = matches!(self.focus.focused(), None | Some(0));
+        let filtering = !self.list.filter().is_empty();
+        match self.list.update(self.list_rect(screen_w, screen_h), list_focused) {
+            ListEvent::Activated(_) => return self.play_selected(),
+            ListEvent::Selected(_) => self.error_message = None,
+            ListEvent::None => {}
+        }
+
+        if self.sort_button.update(self.focus.is_focused(1)) {
+            self.sort = match self.sort {
+                SortOrder::LastPlayed => SortOrder::Name,
+                SortOrder::Name => SortOrder::LastPlayed,
+            };
+            self.sort_button.label = match self.sort {
+                SortOrder::LastPlayed => "Sort: Recent",
+                SortOrder::Name => "Sort: Name",
             }
+            .to_string();
+            let selected = self.selected_world().map(|w| w.dir_name.clone());
+            self.reload(selected.as_deref());
         }
 
-        if self.back_rect(screen_w, screen_h).contains(vec2(mx, my)) {
-            self.back_hovered = true;
-            if is_mouse_button_pressed(MouseButton::Left) {
-                return MenuAction::ChangeState("start".to_string());
+        let mut index = 2;
+        if self.list.selected().is_some() {
+            for i in 0..self.actions.len() {
+                if self.actions[i].update(self.focus.is_focused(index + i)) {
+                    return self.run_action(i);
+                }
+            }
+            index += self.actions.len();
+            // With text in the search, Delete edits it instead.
+            if list_focused && !filtering && is_key_pressed(KeyCode::Delete) {
+                self.open_dialog(Mode::ConfirmDelete);
             }
         }
-        MenuAction::None
+
+        if self.create.update(self.focus.is_focused(index)) {
+            return Navigation::Push(Route::CreateWorld);
+        }
+        if self.back.update(self.focus.is_focused(index + 1)) {
+            return Navigation::Pop;
+        }
+        if is_key_pressed(KeyCode::Escape) && !filtering {
+            return Navigation::Pop;
+        }
+        Navigation::None
     }
+
     fn draw(&mut self, _batch: &mut DrawBatch) {
         clear_background(BLACK);
         let screen_w = screen_width();
         let screen_h = screen_height();
-        draw_text("My Worlds", screen_w / 2.0 - 80.0, 70.0, 40.0, WHITE);
-        for (i, name) in self.worlds.iter().enumerate() {
-            let color = if Some(i) == self.hovered { YELLOW } else { WHITE };
-            draw_rectangle_lines(
-                self.world_rect(i, screen_w).x,
-                self.world_rect(i, screen_w).y,
-                self.world_rect(i, screen_w).w,
-                self.world_rect(i, screen_w).h,
-                2.0,
-                color,
-            );
-            draw_text(
-                name,
-                screen_w / 2.0 - 180.0,
-                155.0 + i as f32 * 60.0,
-                32.0,
-                color,
+        self.layout(screen_w, screen_h);
+
+        self.title.draw(screen_w / 2.0 - 200.0, 60.0);
+        self.sort_button.draw();
+
+        self.list.draw(self.list_rect(screen_w, screen_h), |i, rect, state| {
+            let world = &self.worlds[i];
+            let color = match state {
+                ItemState::Selected => style::HOVER_COLOR,
+                ItemState::Hovered => LIGHTGRAY,
+                ItemState::Normal => style::TEXT_COLOR,
+            };
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, color);
+            if let Some((_, texture)) = self.thumbnails.get(&world.dir_name) {
+                draw_texture_ex(texture, rect.x + 4.0, rect.y + 4.0, WHITE, DrawTextureParams {
+                    dest_size: Some(vec2(rect.h - 8.0, rect.h - 8.0)),
+                    ..Default::default()
+                });
+            }
+            let text_x = rect.x + rect.h + 6.0;
+            style::draw_label(&world.name, text_x, rect.y + 22.0, 26.0, color);
+            let seed = world.metadata.as_ref().map_or("?".to_string(), |m| m.seed_label());
+            let details = format!(
+                "Seed {} | {} | {} played | {}",
+                seed,
+                saves::format_last_played(world.last_played),
+                saves::format_duration(world.play_time),
+                world.size_on_disk.map_or("...".to_string(), saves::format_size),
             );
+            style::draw_label(&details, text_x, rect.y + 42.0, 16.0, style::MUTED_COLOR);
+        });
+        if self.worlds.is_empty() {
+            style::draw_label("No worlds yet", screen_w / 2.0 - 80.0, 150.0, 28.0, style::MUTED_COLOR);
+        } else if self.list.is_empty() {
+            style::draw_label("No worlds match the search", screen_w / 2.0 - 160.0, 150.0, 28.0, style::MUTED_COLOR);
+        }
+
+        if let Some(world) = self.selected_world() {
+            if let Some(metadata) = &world.metadata {
+                let mut details = format!(
+                    "Created {} | {} | {:.0} in-game min",
+                    saves::format_date(metadata.created),
+                    metadata.game_mode.label(),
+                    metadata.world_time / 60.0,
+                );
+                if !metadata.game_version.is_empty() {
+                    details.push_str(&format!(" | v{}", metadata.game_version));
+                }
+                style::draw_label(&details, screen_w / 2.0 - 200.0, screen_h - 298.0, 18.0, style::MUTED_COLOR);
+            }
+            let folder = format!("Folder: {}", world.path.display());
+            style::draw_label(&folder, screen_w / 2.0 - 200.0, screen_h - 276.0, 18.0, style::MUTED_COLOR);
+            for button in &self.actions {
+                button.draw();
+            }
         }
+        if self.mode == Mode::Browse {
+            if let Some(msg) = &self.error_message {
+                style::draw_label(msg, screen_w / 2.0 - 200.0, screen_h - 200.0, 22.0, style::ERROR_COLOR);
+            }
+        }
+
+        self.create.draw();
+        self.back.draw();
 
-        let color = if self.create_hovered { YELLOW } else { WHITE };
-        draw_rectangle_lines(
-            self.create_rect(screen_w, screen_h).x,
-            self.create_rect(screen_w, screen_h).y,
-            self.create_rect(screen_w, screen_h).w,
-            self.create_rect(screen_w, screen_h).h,
-            2.0,
-            color,
-        );
-        draw_text(
-            "Create New World",
-            screen_w / 2.0 - 110.0,
-            screen_h - 145.0,
-            32.0,
-            color,
-        );
-        
-        let color = if self.back_hovered { YELLOW } else { WHITE };
-        draw_rectangle_lines(
-            self.back_rect(screen_w, screen_h).x,
-            self.back_rect(screen_w, screen_h).y,
-            self.back_rect(screen_w, screen_h).w,
-            self.back_rect(screen_w, screen_h).h,
-            2.0,
-            color,
-        );
-        draw_text(
-            "Back",
-            screen_w / 2.0 - 25.0,
-            screen_h - 70.0,
-            28.0,
-            color,
-        );
-    }
-    fn name(&self) -> &str { "worlds" }
-} 
\ No newline at end of file
+        if self.mode != Mode::Browse {
+            self.draw_dialog(screen_w, screen_h);
+        }
+    }
+
+    /// Refreshes the list when coming back from a game or the create screen,
+    /// since play time, thumbnails and the set of worlds may have changed;
+    /// only thumbnails whose file changed are loaded again.
+    /// The search starts over, without what was typed in the meantime.
+    fn resume(&mut self) {
+        let selected = self.selected_world().map(|w| w.dir_name.clone());
+        self.list.reset_filter();
+        self.reload(selected.as_deref());
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::{bail, Context};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::content::{self, Content};
+use crate::utils::metadata::WorldMetadata;
+
+/// Folder with one subfolder per mod, relative to the working directory.
+pub const MODS_DIR: &str = "mods";
+/// Manifest every mod folder needs.
+pub const MOD_MANIFEST: &str = "mod.json";
+
+/// A mod's `mod.json`, for example:
+///
+/// ```json
+/// { "id": "tropics", "name": "Tropics", "version": "0.1.0", "dependencies": [] }
+/// ```
+///
+/// Besides the manifest a mod folder may hold a `content.json` like the base
+/// game's, a `biomes/` folder and the textures they refer to.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModManifest {
+    /// Namespace of everything the mod defines: `tropics:palm_tree`.
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Mods that must be enabled too; they load first.
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+/// An installed mod.
+#[derive(Debug, Clone)]
+pub struct ModInfo {
+    pub manifest: ModManifest,
+    pub dir: PathBuf,
+}
+
+impl ModInfo {
+    pub fn id(&self) -> &str {
+        &self.manifest.id
+    }
+}
+
+/// A world or mod needs mods that are not installed. Repairing the world
+/// cannot help, so the error screen looks for this type.
+#[derive(Debug)]
+pub struct MissingModsError(pub Vec<String>);
+
+impl fmt::Display for MissingModsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Missing mods: {} (not found in {}/)", self.0.join(", "), MODS_DIR)
+    }
+}
+
+impl std::error::Error for MissingModsError {}
+
+/// `id` in the namespace of mod `namespace`.
+pub fn qualify(namespace: &str, id: &str) -> String {
+    format!("{}:{}", namespace, id)
+}
+
+/// What an id written in a mod's files refers to. Ids with a namespace are
+/// taken as they are; plain ids mean the mod's own id when it has one by
+/// that name, and the base game's otherwise.
+pub fn resolve_reference(reference: &str, namespace: Option<&str>, known: &[&str]) -> String {
+    match namespace {
+        Some(namespace) if !reference.contains(':') => {
+            let own = qualify(namespace, reference);
+            if known.contains(&own.as_str()) {
+                own
+            } else {
+                reference.to_string()
+            }
+        }
+        _ => reference.to_string(),
+    }
+}
+
+/// Reads the manifest of every folder in `dir`, sorted by mod id. A missing
+/// `dir` means no mods; broken manifests and two folders with the same mod
+/// id are reported together.
+pub fn discover(dir: &Path) -> anyhow::Result<Vec<ModInfo>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut folders: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read mod folder {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    folders.sort();
+
+    let mut mods: Vec<ModInfo> = Vec::new();
+    let mut errors = Vec::new();
+    for folder in folders {
+        let path = folder.join(MOD_MANIFEST);
+        let manifest = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Ok(serde_json::from_str::<ModManifest>(&text)?));
+        match manifest {
+            Ok(manifest) if !content::valid_id(&manifest.id) => {
+                errors.push(format!("{}: mod id '{}' must be lowercase letters, digits and '_'", path.display(), manifest.id));
+            }
+            Ok(manifest) => {
+                if let Some(other) = mods.iter().find(|m| m.id() == manifest.id) {
+                    errors.push(format!(
+                        "mod id '{}' is used by both {} and {}",
+                        manifest.id,
+                        other.dir.display(),
+                        folder.display()
+                    ));
+                } else {
+                    mods.push(ModInfo { manifest, dir: folder });
+                }
+            }
+            Err(e) => errors.push(format!("{}: {:#}", path.display(), e)),
+        }
+    }
+    if !errors.is_empty() {
+        bail!("Invalid mods:\n  {}", errors.join("\n  "));
+    }
+    mods.sort_by(|a, b| a.id().cmp(b.id()));
+    Ok(mods)
+}
+
+/// The mods named in `enabled`, in load order: every mod after its
+/// dependencies, otherwise alphabetically by id, so the order never depends
+/// on the file system or on the order of `enabled`.
+pub fn resolve(installed: &[ModInfo], enabled: &[String]) -> anyhow::Result<Vec<ModInfo>> {
+    let by_id: HashMap<&str, &ModInfo> = installed.iter().map(|m| (m.id(), m)).collect();
+    let missing: Vec<String> = enabled.iter().filter(|id| !by_id.contains_key(id.as_str())).cloned().collect();
+    if !missing.is_empty() {
+        return Err(MissingModsError(missing).into());
+    }
+    let enabled: BTreeSet<&str> = enabled.iter().map(String::as_str).collect();
+    let mut errors = Vec::new();
+    for id in &enabled {
+        for dependency in &by_id[id].manifest.dependencies {
+            if !enabled.contains(dependency.as_str()) {
+                errors.push(format!("mod '{}' needs '{}', which is not enabled", id, dependency));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        bail!("Missing dependencies:\n  {}", errors.join("\n  "));
+    }
+
+    let mut ordered: Vec<ModInfo> = Vec::new();
+    let mut remaining = enabled;
+    while !remaining.is_empty() {
+        let ready = remaining.iter().copied().find(|id| {
+            by_id[id].manifest.dependencies.iter().all(|d| ordered.iter().any(|m| m.id() == d))
+        });
+        let Some(ready) = ready else {
+            let cycle: Vec<&str> = remaining.into_iter().collect();
+            bail!("Mods depend on each other in a cycle: {}", cycle.join(", "));
+        };
+        remaining.remove(ready);
+        ordered.push(by_id[ready].clone());
+    }
+    Ok(ordered)
+}
+
+/// Ids of every installed mod, in load order; what new worlds enable.
+pub fn installed_ids() -> anyhow::Result<Vec<String>> {
+    let installed = discover(Path::new(MODS_DIR))?;
+    let ids: Vec<String> = installed.iter().map(|m| m.id().to_string()).collect();
+    Ok(resolve(&installed, &ids)?.iter().map(|m| m.id().to_string()).collect())
+}
+
+/// Loads the content of the world's mods and checks the biomes they add,
+/// without putting it in play. Fails with the names of any mod the world
+/// needs that is not installed.
+pub fn content_for_world(metadata: &WorldMetadata) -> anyhow::Result<Content> {
+    let installed = discover(Path::new(MODS_DIR))?;
+    let mods = resolve(&installed, &metadata.mods).context("The world cannot be loaded with the installed mods")?;
+    let content = Content::load(&mods)?;
+    if !mods.is_empty() {
+        crate::validate_biomes(&content)?;
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(mods: &[(&str, &[&str])]) -> Vec<ModInfo> {
+        mods.iter()
+            .map(|(id, dependencies)| ModInfo {
+                manifest: ModManifest {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    version: String::new(),
+                    description: String::new(),
+                    dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+                },
+                dir: PathBuf::from(id),
+            })
+            .collect()
+    }
+
+    fn ids(mods: &[ModInfo]) -> Vec<&str> {
+        mods.iter().map(ModInfo::id).collect()
+    }
+
+    fn names(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn load_order_puts_dependencies_first_then_sorts_by_id() {
+        let installed = installed(&[("zoo", &[]), ("apes", &["zoo"]), ("birds", &[])]);
+        let order = resolve(&installed, &names(&["birds", "apes", "zoo"])).unwrap();
+        assert_eq!(ids(&order), ["birds", "zoo", "apes"]);
+        let again = resolve(&installed, &names(&["zoo", "birds", "apes"])).unwrap();
+        assert_eq!(ids(&again), ids(&order));
+    }
+
+    #[test]
+    fn missing_mods_and_dependencies_are_named() {
+        let installed = installed(&[("apes", &["zoo"]), ("zoo", &[])]);
+        let error = format!("{:#}", resolve(&installed, &names(&["apes", "fish"])).unwrap_err());
+        assert!(error.contains("Missing mods: fish"), "{}", error);
+        let error = format!("{:#}", resolve(&installed, &names(&["apes"])).unwrap_err());
+        assert!(error.contains("'apes' needs 'zoo'"), "{}", error);
+    }
+
+    #[test]
+    fn dependency_cycles_are_reported() {
+        let installed = installed(&[("a", &["b"]), ("b", &["a"])]);
+        assert!(resolve(&installed, &names(&["a", "b"])).is_err());
+    }
+
+    #[test]
+    fn plain_references_prefer_the_mods_own_ids() {
+        let known = ["grass", "tropics:sand", "sand"];
+        assert_eq!(resolve_reference("sand", Some("tropics"), &known), "tropics:sand");
+        assert_eq!(resolve_reference("grass", Some("tropics"), &known), "grass");
+        assert_eq!(resolve_reference("other:x", Some("tropics"), &known), "other:x");
+        assert_eq!(resolve_reference("sand", None, &known), "sand");
+    }
+}
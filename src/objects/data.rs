@@ -0,0 +1,107 @@
+use gaymwtf_core::{Direction, DrawBatch, Object, World, TILE_SIZE};
+use macroquad::prelude::*;
+
+use super::{has_collision, stop_on_overlap};
+use crate::assets::{self, TextureHandle};
+use crate::content::ObjectDef;
+use crate::scripting::{ObjectApi, ScriptInstance, Scripts};
+use rhai::Dynamic;
+use crate::utils::intern::intern;
+
+/// An object described by the content manifest, static unless it has a script.
+#[derive(Clone, Debug)]
+pub struct DataObject {
+    id: &'static str,
+    texture: TextureHandle,
+    pos: Vec2,
+    size: Vec2,
+    /// How far the object moves this frame, in pixels; core collisions stop it.
+    velocity: Vec2,
+    /// The velocity the script asked for, in tiles per second.
+    speed: Vec2,
+    collision: bool,
+    damage: i32,
+    script: Option<ScriptInstance>,
+}
+
+impl DataObject {
+    /// Loads the object's script, if it has one, into the world's `scripts`.
+    pub fn new(def: &ObjectDef, pos: Vec2, scripts: &mut Scripts) -> Self {
+        Self {
+            id: intern(&def.id),
+            texture: assets::texture(&def.texture),
+            pos,
+            size: Vec2::from(def.size),
+            velocity: Vec2::ZERO,
+            speed: Vec2::ZERO,
+            collision: def.collision,
+            damage: def.damage,
+            script: def.script.as_deref().and_then(|path| match scripts.load(path) {
+                Ok(script) => Some(ScriptInstance::new(script)),
+                Err(e) => {
+                    eprintln!("Object '{}' runs without its script: {:#}", def.id, e);
+                    None
+                }
+            }),
+        }
+    }
+
+    pub fn get_texture(&self) -> Texture2D {
+        assets::get(self.texture)
+    }
+
+    pub fn has_collision(&self) -> bool {
+        self.collision
+    }
+
+    pub fn has_handler(&self, name: &str) -> bool {
+        self.script.as_ref().is_some_and(|s| s.has_handler(name))
+    }
+
+    /// Runs script handler `name`, if there is one, with `argument`.
+    fn run_script(&mut self, name: &str, argument: Dynamic, other: Option<&mut dyn Object>, world: Option<&World>) {
+        let Some(script) = &mut self.script else { return };
+        let mut api = ObjectApi { pos: &mut self.pos, velocity: &mut self.speed, other, world };
+        script.run(name, argument, &mut api);
+    }
+
+    /// Moves by what is left of this frame's velocity after collisions, then
+    /// runs the script's `tick` handler and turns its speed into the next
+    /// frame's velocity.
+    pub fn script_tick(&mut self, dt: f32, world: &World) {
+        self.pos += self.velocity;
+        self.run_script("tick", Dynamic::from_float(dt as f64), None, Some(world));
+        self.velocity = self.speed * TILE_SIZE * dt;
+    }
+}
+
+impl Object for DataObject {
+    fn get_type_tag(&self) -> &'static str { self.id }
+    fn get_pos(&self) -> Vec2 { self.pos }
+    fn get_size(&self) -> Vec2 { self.size }
+    fn get_velocity(&self) -> Vec2 { self.velocity }
+
+    fn draw(&self, batch: &mut DrawBatch) {
+        batch.add(self.get_texture(), self.pos, 1.0, Some(self.size));
+    }
+
+    fn set_pos(&mut self, pos: Vec2) { self.pos = pos; }
+    fn set_size(&mut self, size: Vec2) { self.size = size; }
+    fn set_velocity(&mut self, velocity: Vec2) { self.velocity = velocity; }
+
+    fn interact(&mut self, other: &mut dyn Object) {
+        self.run_script("interact", other.get_type_tag().into(), Some(other), None);
+    }
+
+    fn collision(&mut self, other: &mut dyn Object) {
+        if self.damage > 0 {
+            other.hurt(self.damage, Direction::Up);
+        }
+        if self.collision && has_collision(other) {
+            stop_on_overlap(self, other);
+        }
+        self.run_script("collision", other.get_type_tag().into(), Some(other), None);
+    }
+
+    fn clone_box(&self) -> Box<dyn Object> { Box::new(self.clone()) }
+}
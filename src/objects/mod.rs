@@ -1,3 +1,35 @@
-pub mod tree;
-pub mod snow_tree;
-pub mod cactus;
+pub mod data;
+
+pub use data::DataObject;
+
+use gaymwtf_core::Object;
+use macroquad::prelude::*;
+use std::any::Any;
+
+/// Whether `other` stops whatever runs into it. Only manifest objects can
+/// turn collision off.
+pub fn has_collision(other: &dyn Object) -> bool {
+    let other: &dyn Any = other;
+    other.downcast_ref::<DataObject>().is_none_or(DataObject::has_collision)
+}
+
+/// Stops `object` along the axis it overlaps `other` least, as
+/// `Object::collision` does by default.
+pub fn stop_on_overlap(object: &mut dyn Object, other: &dyn Object) {
+    let buffer = vec2(1.0, 1.0);
+    let (min, max) = (object.get_pos() + buffer, object.get_pos() + object.get_size() - buffer);
+    let (other_min, other_max) = (other.get_pos() + buffer, other.get_pos() + other.get_size() - buffer);
+    if min.x >= other_max.x || max.x <= other_min.x || min.y >= other_max.y || max.y <= other_min.y {
+        return;
+    }
+    let x_overlap = (max.x - other_min.x).min(other_max.x - min.x);
+    let y_overlap = (max.y - other_min.y).min(other_max.y - min.y);
+    let mut velocity = object.get_velocity();
+    if x_overlap <= y_overlap {
+        velocity.x = 0.0;
+    }
+    if x_overlap >= y_overlap {
+        velocity.y = 0.0;
+    }
+    object.set_velocity(velocity);
+}
@@ -0,0 +1,168 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::assets;
+use crate::utils::settings::Settings;
+
+/// Folder with one subfolder per resource pack, relative to the working directory.
+pub const PACKS_DIR: &str = "resourcepacks";
+/// Optional description inside a pack folder.
+pub const PACK_MANIFEST: &str = "pack.json";
+/// The base pack every path starts in.
+pub const BASE_DIR: &str = "assets";
+
+/// A pack's optional `pack.json`, for example:
+///
+/// ```json
+/// { "name": "Autumn", "description": "Orange leaves everywhere" }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackManifest {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// An installed resource pack.
+#[derive(Debug, Clone)]
+pub struct PackInfo {
+    /// Folder name, which settings refer to.
+    pub id: String,
+    pub manifest: PackManifest,
+    pub dir: PathBuf,
+}
+
+impl PackInfo {
+    pub fn name(&self) -> &str {
+        if self.manifest.name.is_empty() { &self.id } else { &self.manifest.name }
+    }
+}
+
+/// Lists the packs in `dir` by folder name. A missing folder means no packs;
+/// a broken `pack.json` is reported and the pack still listed.
+pub fn discover(dir: &Path) -> Vec<PackInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut packs: Vec<PackInfo> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .map(|dir| {
+            let manifest_path = dir.join(PACK_MANIFEST);
+            let manifest = match std::fs::read_to_string(&manifest_path) {
+                Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                    eprintln!("Ignoring broken {}: {}", manifest_path.display(), e);
+                    PackManifest::default()
+                }),
+                Err(_) => PackManifest::default(),
+            };
+            let id = dir.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            PackInfo { id, manifest, dir }
+        })
+        .collect();
+    packs.sort_by(|a, b| a.id.cmp(&b.id));
+    packs
+}
+
+/// Where `pack` would keep its version of `path`. Paths in `assets/` lose
+/// that prefix; anything else, such as a mod's texture, keeps its full path.
+pub fn override_path(pack: &Path, path: &str) -> PathBuf {
+    let relative = Path::new(path).strip_prefix(BASE_DIR).unwrap_or(Path::new(path));
+    pack.join(relative)
+}
+
+/// Files to try for `path`, best first: each of `packs` that has it, then
+/// the base file.
+pub fn candidates_in(packs: &[PathBuf], path: &str) -> Vec<PathBuf> {
+    packs
+        .iter()
+        .map(|pack| override_path(pack, path))
+        .filter(|file| file.is_file())
+        .chain(std::iter::once(PathBuf::from(path)))
+        .collect()
+}
+
+static ACTIVE: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+/// [`candidates_in`] the active packs.
+pub fn candidates(path: &str) -> Vec<PathBuf> {
+    candidates_in(&ACTIVE.read().unwrap_or_else(|e| e.into_inner()), path)
+}
+
+/// Enables the packs `ids` in order and reloads textures. Returns the ids
+/// that are not installed; those are skipped.
+pub fn activate(ids: &[String]) -> Vec<String> {
+    let installed = discover(Path::new(PACKS_DIR));
+    let mut missing = Vec::new();
+    let mut dirs = Vec::new();
+    for id in ids {
+        match installed.iter().find(|pack| &pack.id == id) {
+            Some(pack) => dirs.push(pack.dir.clone()),
+            None => missing.push(id.clone()),
+        }
+    }
+    *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = dirs;
+    reload();
+    missing
+}
+
+/// Enables the packs chosen in `settings`.
+pub fn apply_settings(settings: &Settings) {
+    let missing = activate(&settings.resource_packs);
+    if !missing.is_empty() {
+        eprintln!("Resource packs not found in {}/: {}", PACKS_DIR, missing.join(", "));
+    }
+}
+
+/// Drops every loaded texture, so edited pack files show up on the next draw.
+pub fn reload() {
+    assets::manager().reload_all();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn temp_pack(files: &[&str]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for file in files {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn overrides_map_into_the_pack() {
+        let pack = Path::new("resourcepacks/autumn");
+        assert_eq!(override_path(pack, "assets/textures/tiles/grass.png"), pack.join("textures/tiles/grass.png"));
+        assert_eq!(override_path(pack, "mods/tropics/palm.png"), pack.join("mods/tropics/palm.png"));
+    }
+
+    #[test]
+    fn earlier_packs_win_and_missing_files_fall_back() {
+        let first = temp_pack(&["textures/tiles/grass.png"]);
+        let second = temp_pack(&["textures/tiles/grass.png", "textures/tiles/sand.png"]);
+        let packs = [first.path().to_path_buf(), second.path().to_path_buf()];
+        let grass = candidates_in(&packs, "assets/textures/tiles/grass.png");
+        assert_eq!(grass[0], first.path().join("textures/tiles/grass.png"));
+        assert_eq!(grass[1], second.path().join("textures/tiles/grass.png"));
+        assert_eq!(grass[2], Path::new("assets/textures/tiles/grass.png"));
+        let water = candidates_in(&packs, "assets/textures/tiles/water.png");
+        assert_eq!(water, [PathBuf::from("assets/textures/tiles/water.png")]);
+    }
+
+    #[test]
+    fn discovers_packs_with_and_without_manifest() {
+        let temp = temp_pack(&["plain/textures/x.png", "named/pack.json"]);
+        let root = temp.path();
+        std::fs::write(root.join("named/pack.json"), r#"{ "name": "Named", "description": "d" }"#).unwrap();
+        let packs = discover(root);
+        let names: Vec<_> = packs.iter().map(|p| (p.id.as_str(), p.name())).collect();
+        assert_eq!(names, [("named", "Named"), ("plain", "plain")]);
+        assert!(discover(&root.join("nothing")).is_empty());
+    }
+}
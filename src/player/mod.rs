@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod player;
 
 pub use player::{Player, PlayerTextures};
\ No newline at end of file
@@ -1,70 +1,42 @@
 use macroquad::prelude::*;
-use gaymwtf_core::{World, load_texture_sync, Direction, DrawBatch, Object};
-use once_cell::sync::Lazy;
+use gaymwtf_core::{World, Direction, DrawBatch, Object};
+use crate::objects::{has_collision, stop_on_overlap};
+use crate::assets::{self, TextureHandle};
 
 #[derive(Clone)]
 pub struct PlayerTextures {
-    pub idle_up: Texture2D,
-    pub idle_down: Texture2D,
-    pub idle_left: Texture2D,
-    pub idle_right: Texture2D,
-    pub walk_up: [Texture2D; 2],
-    pub walk_down: [Texture2D; 2],
-    pub walk_left: [Texture2D; 2],
-    pub walk_right: [Texture2D; 2],
+    pub idle_up: TextureHandle,
+    pub idle_down: TextureHandle,
+    pub idle_left: TextureHandle,
+    pub idle_right: TextureHandle,
+    pub walk_up: [TextureHandle; 2],
+    pub walk_down: [TextureHandle; 2],
+    pub walk_left: [TextureHandle; 2],
+    pub walk_right: [TextureHandle; 2],
 }
 
-fn load_texture_or_empty(path: &str) -> Texture2D {
-    load_texture_sync(path)
-        .unwrap_or_else(|e| {
-            eprintln!("Failed to load texture {}: {:?}", path, e);
-            Texture2D::empty()
-        })
+impl Default for PlayerTextures {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-static IDLE_UP: Lazy<Texture2D> = Lazy::new(|| load_texture_or_empty("assets/textures/objects/player/idle_up.png"));
-static IDLE_DOWN: Lazy<Texture2D> = Lazy::new(|| load_texture_or_empty("assets/textures/objects/player/idle_down.png"));
-static IDLE_LEFT: Lazy<Texture2D> = Lazy::new(|| load_texture_or_empty("assets/textures/objects/player/idle_left.png"));
-static IDLE_RIGHT: Lazy<Texture2D> = Lazy::new(|| load_texture_or_empty("assets/textures/objects/player/idle_right.png"));
-
-static WALK_UP: Lazy<[Texture2D; 2]> = Lazy::new(|| [
-    load_texture_or_empty("assets/textures/objects/player/walk_up_1.png"),
-    load_texture_or_empty("assets/textures/objects/player/walk_up_2.png"),
-]);
-
-static WALK_DOWN: Lazy<[Texture2D; 2]> = Lazy::new(|| [
-    load_texture_or_empty("assets/textures/objects/player/walk_down_1.png"),
-    load_texture_or_empty("assets/textures/objects/player/walk_down_2.png"),
-]);
-
-static WALK_LEFT: Lazy<[Texture2D; 2]> = Lazy::new(|| [
-    load_texture_or_empty("assets/textures/objects/player/walk_left_1.png"),
-    load_texture_or_empty("assets/textures/objects/player/walk_left_2.png"),
-]);
-
-static WALK_RIGHT: Lazy<[Texture2D; 2]> = Lazy::new(|| [
-    load_texture_or_empty("assets/textures/objects/player/walk_right_1.png"),
-    load_texture_or_empty("assets/textures/objects/player/walk_right_2.png"),
-]);
-
 impl PlayerTextures {
-    pub fn new() -> anyhow::Result<Self> {
-        Ok(Self {
-            idle_up: IDLE_UP.clone(),
-            idle_down: IDLE_DOWN.clone(),
-            idle_left: IDLE_LEFT.clone(),
-            idle_right: IDLE_RIGHT.clone(),
-            walk_up: WALK_UP.clone(),
-            walk_down: WALK_DOWN.clone(),
-            walk_left: WALK_LEFT.clone(),
-            walk_right: WALK_RIGHT.clone(),
-        })
+    pub fn new() -> Self {
+        let texture = |name: &str| assets::texture(&format!("assets/textures/objects/player/{}.png", name));
+        Self {
+            idle_up: texture("idle_up"),
+            idle_down: texture("idle_down"),
+            idle_left: texture("idle_left"),
+            idle_right: texture("idle_right"),
+            walk_up: [texture("walk_up_1"), texture("walk_up_2")],
+            walk_down: [texture("walk_down_1"), texture("walk_down_2")],
+            walk_left: [texture("walk_left_1"), texture("walk_left_2")],
+            walk_right: [texture("walk_right_1"), texture("walk_right_2")],
+        }
     }
 }
 
-
-
-
 #[derive(Clone)]
 pub struct Player {
     pos: Vec2,
@@ -91,6 +63,16 @@ impl Player {
         }
     }
 
+    /// Unit vector pointing where the player is looking.
+    pub fn facing(&self) -> Vec2 {
+        match self.direction {
+            Direction::Up => vec2(0.0, -1.0),
+            Direction::Down => vec2(0.0, 1.0),
+            Direction::Left => vec2(-1.0, 0.0),
+            Direction::Right => vec2(1.0, 0.0),
+        }
+    }
+
     fn handle_input(&mut self) {
         let mut input = Vec2::ZERO;
         if is_key_down(KeyCode::W) { input.y -= 1.0; }
@@ -161,13 +143,19 @@ impl Object for Player {
                 Direction::Right => &self.textures.walk_right[self.animation_frame],
             }
         };
-        batch.add(texture.clone(), self.pos, 1.0, Some(self.get_size()));
+        batch.add(assets::get(*texture), self.pos, 1.0, Some(self.get_size()));
     }
 
     fn set_pos(&mut self, pos: Vec2) { self.pos = pos; }
     fn set_size(&mut self, size: Vec2) { self.size = size; }
     fn set_velocity(&mut self, velocity: Vec2) { self.velocity = velocity; }
-    
+
+    fn collision(&mut self, other: &mut dyn Object) {
+        if has_collision(other) {
+            stop_on_overlap(self, other);
+        }
+    }
+
     fn clone_box(&self) -> Box<dyn Object> {
         Box::new(self.clone())
     }
@@ -0,0 +1,513 @@
+//! Object behaviour written in [Rhai](https://rhai.rs). Scripts only see what
+//! [`ObjectApi`] hands them: no files, no modules, and the engine's limits on
+//! operations, nesting, call depth and string and collection sizes.
+//!
+//! Top-level `let`s become fields of `this`, which keeps them per object
+//! between handler calls; Rhai functions cannot see outer variables.
+//!
+//! ```text
+//! let hops = 0;
+//!
+//! fn tick(dt) {
+//!     if random(0, 1) < dt {
+//!         set_velocity(random(-2, 2), random(-2, 2));
+//!     }
+//! }
+//!
+//! fn collision(other) {
+//!     if other == "player" { hurt(1); }
+//! }
+//!
+//! fn interact(other) {
+//!     this.hops += 1;
+//!     spawn_object("cactus", x() + 1, y());
+//! }
+//! ```
+
+use anyhow::{bail, Context};
+use gaymwtf_core::{Direction, Object, World, CHUNK_SIZE, TILE_SIZE};
+use macroquad::prelude::*;
+use once_cell::sync::Lazy;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, ImmutableString, Map, Scope, AST};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::objects::DataObject;
+
+/// Handlers the game calls, with the parameters each takes.
+pub const HANDLERS: [(&str, usize); 3] = [("tick", 1), ("collision", 1), ("interact", 1)];
+/// Objects all scripts together may spawn in one frame.
+pub const SPAWN_LIMIT: usize = 64;
+/// Operations one handler may run before it is stopped, so a runaway loop
+/// costs a frame instead of hanging the game.
+pub const STEP_LIMIT: u64 = 50_000;
+/// How deeply expressions and blocks may nest.
+pub const NESTING_LIMIT: usize = 64;
+/// How deep script functions may call each other.
+pub const CALL_DEPTH_LIMIT: usize = 32;
+/// Longest string, in bytes, a script may build.
+pub const STRING_LIMIT: usize = 4096;
+/// Most elements an array or object map may hold.
+pub const COLLECTION_LIMIT: usize = 1024;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// The object whose handler is running, copied in for the call.
+struct Current {
+    pos: Vec2,
+    velocity: Vec2,
+    has_other: bool,
+    /// Damage dealt to the other object, applied once the handler returns.
+    hurts: Vec<i32>,
+    /// Spawns asked for earlier this frame, which count towards the limit.
+    spawned: usize,
+    spawns: Vec<(String, Vec2)>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Current>> = const { RefCell::new(None) };
+    /// What `print` and `debug` wrote since it was last taken.
+    static PRINTED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn take_printed() -> Vec<String> {
+    PRINTED.with(|printed| std::mem::take(&mut *printed.borrow_mut()))
+}
+
+scoped_tls::scoped_thread_local!(static WORLD: World);
+
+fn with_current<T>(name: &str, f: impl FnOnce(&mut Current) -> ScriptResult<T>) -> ScriptResult<T> {
+    CURRENT.with(|current| match current.borrow_mut().as_mut() {
+        Some(current) => f(current),
+        None => Err(format!("{}() only works inside a handler", name).into()),
+    })
+}
+
+fn number(value: &Dynamic, index: usize) -> ScriptResult<f64> {
+    let n = match value.as_float() {
+        Ok(n) => n,
+        Err(_) => value
+            .as_int()
+            .map(|n| n as f64)
+            .map_err(|type_name| format!("argument {} must be a number, got {}", index + 1, type_name))?,
+    };
+    if !n.is_finite() {
+        return Err(format!("argument {} must be finite", index + 1).into());
+    }
+    Ok(n)
+}
+
+fn tile_at(world: &World, x: i32, y: i32) -> Option<&'static str> {
+    let chunk_pos = (x.div_euclid(CHUNK_SIZE as i32), y.div_euclid(CHUNK_SIZE as i32));
+    let pos = vec2(x as f32, y as f32) * TILE_SIZE;
+    world.chunks.get(&chunk_pos)?.tiles.iter().find(|t| t.get_pos() == pos).map(|t| t.get_type_tag())
+}
+
+/// A sandboxed engine with the game's functions. Positions are in tiles,
+/// velocities in tiles per second.
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(STEP_LIMIT)
+        .set_max_expr_depths(NESTING_LIMIT, NESTING_LIMIT)
+        .set_max_call_levels(CALL_DEPTH_LIMIT)
+        .set_max_string_size(STRING_LIMIT)
+        .set_max_array_size(COLLECTION_LIMIT)
+        .set_max_map_size(COLLECTION_LIMIT)
+        .on_print(|text| PRINTED.with(|printed| printed.borrow_mut().push(text.to_string())))
+        .on_debug(|text, _, _| PRINTED.with(|printed| printed.borrow_mut().push(text.to_string())));
+    engine
+        .register_fn("random", |a: Dynamic, b: Dynamic| -> ScriptResult<f64> {
+            Ok(macroquad::rand::gen_range(number(&a, 0)?, number(&b, 1)?))
+        })
+        .register_fn("x", || with_current("x", |c| Ok((c.pos.x / TILE_SIZE) as f64)))
+        .register_fn("y", || with_current("y", |c| Ok((c.pos.y / TILE_SIZE) as f64)))
+        .register_fn("set_pos", |x: Dynamic, y: Dynamic| {
+            let pos = vec2(number(&x, 0)? as f32, number(&y, 1)? as f32) * TILE_SIZE;
+            with_current("set_pos", |c| {
+                c.pos = pos;
+                Ok(())
+            })
+        })
+        .register_fn("vx", || with_current("vx", |c| Ok(c.velocity.x as f64)))
+        .register_fn("vy", || with_current("vy", |c| Ok(c.velocity.y as f64)))
+        .register_fn("set_velocity", |x: Dynamic, y: Dynamic| {
+            let velocity = vec2(number(&x, 0)? as f32, number(&y, 1)? as f32);
+            with_current("set_velocity", |c| {
+                c.velocity = velocity;
+                Ok(())
+            })
+        })
+        .register_fn("spawn_object", |id: ImmutableString, x: Dynamic, y: Dynamic| {
+            let pos = vec2(number(&x, 1)? as f32, number(&y, 2)? as f32) * TILE_SIZE;
+            with_current("spawn_object", |c| {
+                if c.spawned + c.spawns.len() >= SPAWN_LIMIT {
+                    return Err(format!("more than {} spawns this frame", SPAWN_LIMIT).into());
+                }
+                c.spawns.push((id.to_string(), pos));
+                Ok(())
+            })
+        })
+        .register_fn("hurt", |damage: Dynamic| {
+            let damage = number(&damage, 0)? as i32;
+            with_current("hurt", |c| {
+                if !c.has_other {
+                    return Err("only collision() and interact() have another object".into());
+                }
+                c.hurts.push(damage);
+                Ok(())
+            })
+        })
+        .register_fn("tile", |x: Dynamic, y: Dynamic| -> ScriptResult<Dynamic> {
+            let (x, y) = (number(&x, 0)?.floor() as i32, number(&y, 1)?.floor() as i32);
+            let tile = WORLD.is_set().then(|| WORLD.with(|world| tile_at(world, x, y))).flatten();
+            Ok(tile.map_or(Dynamic::UNIT, Dynamic::from))
+        });
+    engine
+}
+
+static ENGINE: Lazy<Engine> = Lazy::new(engine);
+
+/// What a world's scripts asked of the game since it last looked.
+#[derive(Debug, Default)]
+struct Outbox {
+    spawns: Vec<(String, Vec2)>,
+    printed: Vec<String>,
+}
+
+type SharedOutbox = Arc<Mutex<Outbox>>;
+
+/// A compiled script file.
+#[derive(Debug)]
+pub struct Script {
+    pub path: String,
+    ast: AST,
+    /// Where its spawns and output go: the world's [`Scripts`].
+    outbox: SharedOutbox,
+}
+
+impl Script {
+    /// Compiles `source` and checks the handlers' parameters and the initial
+    /// state, so mistakes show up when content loads rather than in play.
+    pub fn compile(path: &str, source: &str) -> anyhow::Result<Self> {
+        let ast = ENGINE.compile(source)?;
+        for function in ast.iter_functions() {
+            let expected = HANDLERS.iter().find(|(name, _)| *name == function.name).map(|(_, params)| *params);
+            if let Some(params) = expected.filter(|params| *params != function.params.len()) {
+                let noun = if params == 1 { "parameter" } else { "parameters" };
+                bail!("handler {}() must take {} {}", function.name, params, noun);
+            }
+        }
+        let script = Self { path: path.to_string(), ast, outbox: SharedOutbox::default() };
+        let checked = script.initial_state().map(drop);
+        take_printed();
+        checked?;
+        Ok(script)
+    }
+
+    pub fn has_handler(&self, name: &str) -> bool {
+        self.ast.iter_functions().any(|function| function.name == name)
+    }
+
+    /// Runs the top-level statements and gathers their `let`s into a map.
+    fn initial_state(&self) -> ScriptResult<Dynamic> {
+        let mut scope = Scope::new();
+        ENGINE.run_ast_with_scope(&mut scope, &self.ast)?;
+        let state: Map = scope.iter().map(|(name, _, value)| (name.into(), value)).collect();
+        Ok(state.into())
+    }
+
+    /// Hands what the script printed to its world, marked with the file.
+    fn forward_printed(&self) {
+        let printed = take_printed().into_iter().map(|text| format!("{}: {}", self.path, text));
+        self.outbox.lock().unwrap().printed.extend(printed);
+    }
+}
+
+/// The scripts of one world and what their handlers asked of it. Each world
+/// in play has its own, so nothing carries over to the next world loaded.
+#[derive(Default)]
+pub struct Scripts {
+    compiled: HashMap<String, Arc<Script>>,
+    outbox: SharedOutbox,
+}
+
+impl Scripts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads and compiles the script at `path`, once per path.
+    pub fn load(&mut self, path: &str) -> anyhow::Result<Arc<Script>> {
+        if let Some(script) = self.compiled.get(path) {
+            return Ok(script.clone());
+        }
+        let source = std::fs::read_to_string(Path::new(path)).with_context(|| format!("Failed to read script {}", path))?;
+        let mut script = Script::compile(path, &source).with_context(|| format!("Invalid script {}", path))?;
+        script.outbox = self.outbox.clone();
+        let script = Arc::new(script);
+        self.compiled.insert(path.to_string(), script.clone());
+        Ok(script)
+    }
+
+    /// Spawns asked for since the last call, as object id and world position.
+    pub fn take_spawns(&self) -> Vec<(String, Vec2)> {
+        std::mem::take(&mut self.outbox.lock().unwrap().spawns)
+    }
+
+    /// Lines scripts printed since the last call, for the console.
+    pub fn take_printed(&self) -> Vec<String> {
+        std::mem::take(&mut self.outbox.lock().unwrap().printed)
+    }
+}
+
+/// One object's copy of a script: `this`, with its top-level `let`s, lives here.
+#[derive(Debug, Clone)]
+pub struct ScriptInstance {
+    script: Arc<Script>,
+    state: Dynamic,
+    failed: bool,
+}
+
+impl ScriptInstance {
+    pub fn new(script: Arc<Script>) -> Self {
+        // Compiling already ran this once, so only `random` can make it differ.
+        let (state, failed) = match script.initial_state() {
+            Ok(state) => (state, false),
+            Err(_) => (Dynamic::UNIT, true),
+        };
+        script.forward_printed();
+        Self { script, state, failed }
+    }
+
+    pub fn has_handler(&self, name: &str) -> bool {
+        !self.failed && self.script.has_handler(name)
+    }
+
+    /// Runs handler `name` if the script has it. A failing script is
+    /// reported once and then stays off for this object.
+    pub fn run(&mut self, name: &str, argument: Dynamic, api: &mut ObjectApi) {
+        if !self.has_handler(name) {
+            return;
+        }
+        let current = Current {
+            pos: *api.pos,
+            velocity: *api.velocity,
+            has_other: api.other.is_some(),
+            hurts: Vec::new(),
+            spawned: self.script.outbox.lock().unwrap().spawns.len(),
+            spawns: Vec::new(),
+        };
+        CURRENT.with(|c| *c.borrow_mut() = Some(current));
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(&mut self.state);
+        let call = || ENGINE.call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &self.script.ast, name, (argument,));
+        let result = match api.world {
+            Some(world) => WORLD.set(world, call),
+            None => call(),
+        };
+        let current = CURRENT.with(|c| c.borrow_mut().take()).expect("set above");
+        *api.pos = current.pos;
+        *api.velocity = current.velocity;
+        self.script.outbox.lock().unwrap().spawns.extend(current.spawns);
+        self.script.forward_printed();
+        if let Some(other) = api.other.as_deref_mut() {
+            for damage in current.hurts {
+                other.hurt(damage, Direction::Up);
+            }
+        }
+        if let Err(e) = result {
+            eprintln!("Script {} failed in {}(), {}; disabled for this object", self.script.path, name, e);
+            self.failed = true;
+        }
+    }
+}
+
+/// The part of the game a running handler can touch.
+pub struct ObjectApi<'a> {
+    pub pos: &'a mut Vec2,
+    /// In tiles per second.
+    pub velocity: &'a mut Vec2,
+    /// The object being touched or interacting, if any.
+    pub other: Option<&'a mut dyn Object>,
+    pub world: Option<&'a World>,
+}
+
+/// Runs the `tick` handler of every scripted object in `chunks`. Core ticks
+/// objects with their own chunk taken out of the world, so scripts tick here
+/// instead, with each chunk's objects set aside while the tiles stay readable.
+pub fn tick_objects(world: &mut World, chunks: &[(i32, i32)], dt: f32) {
+    for chunk_pos in chunks {
+        let Some(chunk) = world.chunks.get_mut(chunk_pos) else { continue };
+        let mut objects = std::mem::take(&mut chunk.objects);
+        for object in &mut objects {
+            let object: &mut dyn Any = &mut **object;
+            if let Some(object) = object.downcast_mut::<DataObject>() {
+                object.script_tick(dt, world);
+            }
+        }
+        if let Some(chunk) = world.chunks.get_mut(chunk_pos) {
+            chunk.objects = objects;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rhai::ParseErrorType;
+
+    fn run(source: &str, function: &str) -> ScriptResult<Dynamic> {
+        let ast = ENGINE.compile(source).map_err(Box::<EvalAltResult>::from)?;
+        ENGINE.call_fn(&mut Scope::new(), &ast, function, ())
+    }
+
+    #[test]
+    fn evaluates_arithmetic_and_control_flow() {
+        let source = r#"
+            fn fib(n) {
+                if n < 2 { return n; }
+                return fib(n - 1) + fib(n - 2);
+            }
+            fn main() {
+                let total = 0;
+                let i = 0;
+                while i < 5 { total += i * 2; i += 1; }
+                if total == 20 && !false { "sum " + total + " fib " + fib(10) } else { () }
+            }
+        "#;
+        assert_eq!(run(source, "main").unwrap().into_string().unwrap(), "sum 20 fib 55");
+    }
+
+    #[test]
+    fn state_persists_between_handler_calls() {
+        let source = "let count = 0; fn tick(dt) { this.count += dt; set_pos(this.count, 0); }";
+        let mut instance = ScriptInstance::new(Arc::new(Script::compile("test", source).unwrap()));
+        let (mut pos, mut velocity) = (Vec2::ZERO, Vec2::ZERO);
+        let mut api = ObjectApi { pos: &mut pos, velocity: &mut velocity, other: None, world: None };
+        instance.run("tick", Dynamic::from_float(1.0), &mut api);
+        instance.run("tick", Dynamic::from_float(2.0), &mut api);
+        assert_eq!(pos, vec2(3.0, 0.0) * TILE_SIZE);
+        assert!(instance.has_handler("tick"));
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        let error = run("fn main() { while true { } }", "main").unwrap_err();
+        assert!(matches!(*error, EvalAltResult::ErrorTooManyOperations(_)), "{}", error);
+        let error = run("fn main() { main(); }", "main").unwrap_err();
+        assert!(matches!(*error, EvalAltResult::ErrorStackOverflow(_)), "{}", error);
+        let error = run("fn main() { let s = \"ab\"; loop { s += s; } }", "main").unwrap_err();
+        assert!(matches!(*error, EvalAltResult::ErrorDataTooLarge(..)), "{}", error);
+        let error = run("fn main() { let a = []; loop { a.push(1); } }", "main").unwrap_err();
+        assert!(matches!(*error, EvalAltResult::ErrorDataTooLarge(..)), "{}", error);
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected() {
+        let too_deep = |source: &str| {
+            matches!(ENGINE.compile(source).unwrap_err().err_type(), ParseErrorType::ExprTooDeep)
+        };
+        assert!(too_deep(&format!("fn main() {{ {}1{} }}", "(".repeat(100_000), ")".repeat(100_000))));
+        assert!(too_deep(&format!("fn main() {{ {}1 }}", "- ".repeat(100_000))));
+        assert!(too_deep(&format!("fn main() {{ {}{} }}", "if true { ".repeat(10_000), "}".repeat(10_000))));
+        let fine = format!("fn main() {{ {}1{} }}", "(".repeat(20), ")".repeat(20));
+        assert_eq!(run(&fine, "main").unwrap().as_int(), Ok(1));
+    }
+
+    #[test]
+    fn scripts_cannot_reach_outside() {
+        assert!(run("import \"std\" as s; fn main() {}", "main").is_err());
+        assert!(run("fn main() { open(\"/etc/passwd\") }", "main").is_err());
+        // Game functions need an object to act on.
+        assert!(Script::compile("test", "let start = x();").is_err());
+    }
+
+    #[test]
+    fn checks_handler_parameters() {
+        let error = Script::compile("test", "fn tick() {}").unwrap_err();
+        assert_eq!(error.to_string(), "handler tick() must take 1 parameter");
+        assert!(Script::compile("test", "fn helper() {} fn tick(dt) { helper(); }").is_ok());
+    }
+
+    #[test]
+    fn failing_scripts_turn_off() {
+        let script = Arc::new(Script::compile("test", "fn tick(dt) { set_pos(dt, 0); 1 + () }").unwrap());
+        let mut instance = ScriptInstance::new(script);
+        let (mut pos, mut velocity) = (Vec2::ZERO, Vec2::ZERO);
+        let mut api = ObjectApi { pos: &mut pos, velocity: &mut velocity, other: None, world: None };
+        instance.run("tick", Dynamic::from_float(1.0), &mut api);
+        assert!(!instance.has_handler("tick"));
+        // What the handler did before failing still happened.
+        assert_eq!(pos, vec2(1.0, 0.0) * TILE_SIZE);
+    }
+
+    #[test]
+    fn spawns_stay_with_their_world() {
+        let (first, second) = (Scripts::new(), Scripts::new());
+        let mut script = Script::compile("test", "fn interact(other) { spawn_object(\"cactus\", 1, 2); }").unwrap();
+        script.outbox = first.outbox.clone();
+        let mut instance = ScriptInstance::new(Arc::new(script));
+        let (mut pos, mut velocity) = (Vec2::ZERO, Vec2::ZERO);
+        let mut api = ObjectApi { pos: &mut pos, velocity: &mut velocity, other: None, world: None };
+        instance.run("interact", "player".into(), &mut api);
+        assert!(second.take_spawns().is_empty());
+        assert_eq!(first.take_spawns(), [("cactus".to_string(), vec2(1.0, 2.0) * TILE_SIZE)]);
+        assert!(first.take_spawns().is_empty());
+    }
+
+    #[test]
+    fn printed_lines_go_to_the_world() {
+        let scripts = Scripts::new();
+        let source = "print(\"loaded\"); fn tick(dt) { print(`tick ${dt}`); debug(1); }";
+        let mut script = Script::compile("frog.rhai", source).unwrap();
+        // Checking the script while compiling prints nothing.
+        assert!(take_printed().is_empty());
+        script.outbox = scripts.outbox.clone();
+        let mut instance = ScriptInstance::new(Arc::new(script));
+        let (mut pos, mut velocity) = (Vec2::ZERO, Vec2::ZERO);
+        let mut api = ObjectApi { pos: &mut pos, velocity: &mut velocity, other: None, world: None };
+        instance.run("tick", Dynamic::from_float(0.5), &mut api);
+        assert_eq!(scripts.take_printed(), ["frog.rhai: loaded", "frog.rhai: tick 0.5", "frog.rhai: 1"]);
+        assert!(scripts.take_printed().is_empty());
+    }
+
+    /// Counts the damage it takes.
+    #[derive(Clone)]
+    struct Target(i32);
+
+    impl Object for Target {
+        fn get_type_tag(&self) -> &'static str { "target" }
+        fn get_pos(&self) -> Vec2 { Vec2::ZERO }
+        fn get_size(&self) -> Vec2 { Vec2::ONE }
+        fn get_velocity(&self) -> Vec2 { Vec2::ZERO }
+        fn draw(&self, _batch: &mut gaymwtf_core::DrawBatch) {}
+        fn set_size(&mut self, _size: Vec2) {}
+        fn set_pos(&mut self, _pos: Vec2) {}
+        fn set_velocity(&mut self, _velocity: Vec2) {}
+        fn hurt(&mut self, damage: i32, _attack_dir: Direction) { self.0 += damage; }
+        fn clone_box(&self) -> Box<dyn Object> { Box::new(self.clone()) }
+    }
+
+    #[test]
+    fn object_api_moves_and_hurts() {
+        let source = "fn collision(other) { set_pos(x() + 1, y()); set_velocity(vx() + 2, 0); hurt(3); }";
+        let mut instance = ScriptInstance::new(Arc::new(Script::compile("test", source).unwrap()));
+        let (mut pos, mut velocity, mut target) = (vec2(16.0, 32.0), Vec2::ZERO, Target(0));
+        let mut api = ObjectApi { pos: &mut pos, velocity: &mut velocity, other: Some(&mut target), world: None };
+        instance.run("collision", "target".into(), &mut api);
+        assert!(instance.has_handler("collision"));
+        assert_eq!(target.0, 3);
+        assert_eq!(pos, vec2(32.0, 32.0));
+        assert_eq!(velocity, vec2(2.0, 0.0));
+        // Without another object hurt() fails and the script turns off.
+        let mut api = ObjectApi { pos: &mut pos, velocity: &mut velocity, other: None, world: None };
+        instance.run("collision", Dynamic::UNIT, &mut api);
+        assert!(!instance.has_handler("collision"));
+        assert_eq!(velocity, vec2(4.0, 0.0));
+    }
+}
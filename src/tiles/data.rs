@@ -0,0 +1,42 @@
+use gaymwtf_core::{DrawBatch, Tile};
+use macroquad::prelude::*;
+
+use crate::assets::{self, TextureHandle};
+use crate::content::TileDef;
+use crate::utils::intern::intern;
+
+/// A tile described by the content manifest.
+#[derive(Clone, Debug)]
+pub struct DataTile {
+    id: &'static str,
+    texture: TextureHandle,
+    pos: Vec2,
+    size: Vec2,
+}
+
+impl DataTile {
+    pub fn new(def: &TileDef, pos: Vec2) -> Self {
+        Self {
+            id: intern(&def.id),
+            texture: assets::texture(&def.texture),
+            pos,
+            size: Vec2::from(def.size),
+        }
+    }
+
+    pub fn get_texture(&self) -> Texture2D {
+        assets::get(self.texture)
+    }
+}
+
+impl Tile for DataTile {
+    fn get_type_tag(&self) -> &'static str { self.id }
+    fn get_pos(&self) -> Vec2 { self.pos }
+    fn set_pos(&mut self, pos: Vec2) { self.pos = pos; }
+    fn get_size(&self) -> Vec2 { self.size }
+    fn clone_box(&self) -> Box<dyn Tile> { Box::new(self.clone()) }
+
+    fn draw(&self, batch: &mut DrawBatch, pos: Vec2) {
+        batch.add(self.get_texture(), pos, 0.0, Some(self.size));
+    }
+}
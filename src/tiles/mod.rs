@@ -1,4 +1,3 @@
-pub mod grass;
-pub mod sand;
-pub mod snowgrass;
-pub mod water;
+pub mod data;
+
+pub use data::DataTile;
@@ -0,0 +1,45 @@
+use macroquad::prelude::*;
+use crate::ui::focus::activate_pressed;
+use crate::ui::style;
+
+pub struct Button {
+    pub label: String,
+    pub rect: Rect,
+    pub font_size: f32,
+    hovered: bool,
+    pressed: bool,
+    focused: bool,
+}
+
+impl Button {
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            rect: Rect::default(),
+            font_size: 28.0,
+            hovered: false,
+            pressed: false,
+            focused: false,
+        }
+    }
+
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Returns true when the button was clicked or activated from the keyboard.
+    pub fn update(&mut self, focused: bool) -> bool {
+        self.focused = focused;
+        self.hovered = self.rect.contains(Vec2::from(mouse_position()));
+        self.pressed = self.hovered && is_mouse_button_down(MouseButton::Left);
+        (self.hovered && is_mouse_button_released(MouseButton::Left)) || (focused && activate_pressed())
+    }
+
+    pub fn draw(&self) {
+        let tint = if self.pressed { GRAY } else { WHITE };
+        style::draw_button_frame(self.rect, tint);
+        let color = if self.hovered || self.focused { style::HOVER_COLOR } else { style::TEXT_COLOR };
+        style::draw_label_centered(&self.label, self.rect, self.font_size, color);
+    }
+}
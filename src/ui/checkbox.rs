@@ -0,0 +1,41 @@
+use macroquad::prelude::*;
+use crate::ui::focus::activate_pressed;
+use crate::ui::style;
+
+pub struct Checkbox {
+    pub label: String,
+    pub rect: Rect,
+    pub checked: bool,
+    hovered: bool,
+    focused: bool,
+}
+
+impl Checkbox {
+    pub fn new(label: &str, checked: bool) -> Self {
+        Self { label: label.to_string(), rect: Rect::default(), checked, hovered: false, focused: false }
+    }
+
+    /// Returns true when the checkbox was toggled.
+    pub fn update(&mut self, focused: bool) -> bool {
+        self.focused = focused;
+        self.hovered = self.rect.contains(Vec2::from(mouse_position()));
+        let toggled = (self.hovered && is_mouse_button_released(MouseButton::Left)) || (focused && activate_pressed());
+        if toggled {
+            self.checked = !self.checked;
+        }
+        toggled
+    }
+
+    pub fn draw(&self) {
+        let color = if self.hovered || self.focused { style::HOVER_COLOR } else { style::TEXT_COLOR };
+        let size = self.rect.h;
+        draw_rectangle_lines(self.rect.x, self.rect.y, size, size, 2.0, color);
+        if self.checked {
+            let inset = size * 0.25;
+            draw_rectangle(self.rect.x + inset, self.rect.y + inset, size - inset * 2.0, size - inset * 2.0, color);
+        }
+        let text_rect = Rect::new(self.rect.x + size + 12.0, self.rect.y, self.rect.w - size - 12.0, size);
+        let dims = style::measure(&self.label, size * 0.8);
+        style::draw_label(&self.label, text_rect.x, text_rect.y + (size + dims.offset_y) / 2.0, size * 0.8, color);
+    }
+}
@@ -0,0 +1,79 @@
+use macroquad::prelude::*;
+
+/// Tracks which widget of a menu has keyboard focus.
+///
+/// Tab and Shift+Tab cycle through the widgets; with [`FocusRing::with_arrows`]
+/// the Up and Down keys do too. Clicking a widget focuses it.
+pub struct FocusRing {
+    focused: Option<usize>,
+    len: usize,
+    arrows: bool,
+}
+
+impl FocusRing {
+    pub fn new(len: usize) -> Self {
+        Self { focused: None, len, arrows: false }
+    }
+
+    pub fn with_arrows(mut self) -> Self {
+        self.arrows = true;
+        self
+    }
+
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+        if self.focused.is_some_and(|f| f >= len) {
+            self.focused = None;
+        }
+    }
+
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    pub fn is_focused(&self, i: usize) -> bool {
+        self.focused == Some(i)
+    }
+
+    pub fn focus(&mut self, i: Option<usize>) {
+        self.focused = i.filter(|&i| i < self.len);
+    }
+
+    fn step(&mut self, forward: bool) {
+        if self.len == 0 {
+            return;
+        }
+        self.focused = Some(match (self.focused, forward) {
+            (None, true) => 0,
+            (None, false) => self.len - 1,
+            (Some(i), true) => (i + 1) % self.len,
+            (Some(i), false) => (i + self.len - 1) % self.len,
+        });
+    }
+
+    /// `rects` are the widget rects in focus order.
+    pub fn update(&mut self, rects: &[Rect]) {
+        self.set_len(rects.len());
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if is_key_pressed(KeyCode::Tab) {
+            self.step(!shift);
+        }
+        if self.arrows && is_key_pressed(KeyCode::Down) {
+            self.step(true);
+        }
+        if self.arrows && is_key_pressed(KeyCode::Up) {
+            self.step(false);
+        }
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let mouse = Vec2::from(mouse_position());
+            if let Some(i) = rects.iter().position(|r| r.contains(mouse)) {
+                self.focused = Some(i);
+            }
+        }
+    }
+}
+
+/// True when the focused widget should activate (Enter or Space).
+pub fn activate_pressed() -> bool {
+    is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) || is_key_pressed(KeyCode::Space)
+}
@@ -0,0 +1,32 @@
+use macroquad::prelude::*;
+use crate::ui::style;
+
+pub struct Label {
+    pub text: String,
+    pub font_size: f32,
+    pub color: Color,
+}
+
+impl Label {
+    pub fn new(text: &str, font_size: f32) -> Self {
+        Self { text: text.to_string(), font_size, color: style::TEXT_COLOR }
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn width(&self) -> f32 {
+        style::measure(&self.text, self.font_size).width
+    }
+
+    /// Draws the label with its baseline at `y`.
+    pub fn draw(&self, x: f32, y: f32) {
+        style::draw_label(&self.text, x, y, self.font_size, self.color);
+    }
+
+    pub fn draw_centered(&self, rect: Rect) {
+        style::draw_label_centered(&self.text, rect, self.font_size, self.color);
+    }
+}
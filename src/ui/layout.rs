@@ -0,0 +1,44 @@
+use macroquad::prelude::*;
+
+/// Stacks rows of a fixed width from top to bottom.
+pub struct Column {
+    x: f32,
+    y: f32,
+    w: f32,
+    spacing: f32,
+}
+
+impl Column {
+    pub fn new(x: f32, y: f32, w: f32, spacing: f32) -> Self {
+        Self { x, y, w, spacing }
+    }
+
+    pub fn centered(center_x: f32, y: f32, w: f32, spacing: f32) -> Self {
+        Self::new(center_x - w / 2.0, y, w, spacing)
+    }
+
+    pub fn next(&mut self, h: f32) -> Rect {
+        let rect = Rect::new(self.x, self.y, self.w, h);
+        self.y += h + self.spacing;
+        rect
+    }
+
+    pub fn skip(&mut self, h: f32) {
+        self.y += h;
+    }
+}
+
+/// Splits `rect` into `n` equal cells side by side, `gap` pixels apart.
+pub fn split_row(rect: Rect, n: usize, gap: f32) -> Vec<Rect> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let w = (rect.w - gap * (n - 1) as f32) / n as f32;
+    (0..n)
+        .map(|i| Rect::new(rect.x + i as f32 * (w + gap), rect.y, w, rect.h))
+        .collect()
+}
+
+pub fn centered_rect(center: Vec2, w: f32, h: f32) -> Rect {
+    Rect::new(center.x - w / 2.0, center.y - h / 2.0, w, h)
+}
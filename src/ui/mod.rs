@@ -0,0 +1,18 @@
+pub mod button;
+pub mod checkbox;
+pub mod focus;
+pub mod label;
+pub mod layout;
+pub mod scroll_list;
+pub mod slider;
+pub mod style;
+pub mod text_input;
+
+pub use button::Button;
+pub use checkbox::Checkbox;
+pub use focus::FocusRing;
+pub use label::Label;
+pub use layout::{centered_rect, split_row, Column};
+pub use scroll_list::{ItemState, ListEvent, ScrollList};
+pub use slider::Slider;
+pub use text_input::TextInput;
@@ -0,0 +1,294 @@
+use macroquad::prelude::*;
+use crate::ui::TextInput;
+
+const FILTER_HEIGHT: f32 = 30.0;
+const SCROLLBAR_WIDTH: f32 = 6.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ItemState {
+    Normal,
+    Hovered,
+    Selected,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ListEvent {
+    None,
+    /// An item was selected by click or keyboard; carries the item index.
+    Selected(usize),
+    /// The selected item was clicked again or Enter was pressed.
+    Activated(usize),
+}
+
+/// A vertical list that scrolls by whole rows, with optional type-to-filter.
+///
+/// Indices passed to callers always refer to the unfiltered item list given
+/// to [`ScrollList::set_items`].
+pub struct ScrollList {
+    item_height: f32,
+    spacing: f32,
+    first_visible: usize,
+    selected: Option<usize>,
+    hovered: Option<usize>,
+    labels: Vec<String>,
+    filtered: Vec<usize>,
+    filter: Option<TextInput>,
+}
+
+impl ScrollList {
+    pub fn new(item_height: f32, spacing: f32) -> Self {
+        Self {
+            item_height,
+            spacing,
+            first_visible: 0,
+            selected: None,
+            hovered: None,
+            labels: Vec::new(),
+            filtered: Vec::new(),
+            filter: None,
+        }
+    }
+
+    pub fn with_filter(mut self) -> Self {
+        self.filter = Some(TextInput::new(32).with_placeholder("Type to search...").with_font_size(22.0));
+        self
+    }
+
+    /// Replaces the items, keeping the filter. The selection is cleared.
+    pub fn set_items(&mut self, labels: Vec<String>) {
+        self.labels = labels;
+        self.selected = None;
+        self.hovered = None;
+        self.apply_filter();
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index.filter(|i| self.filtered.contains(i));
+        self.scroll_to_selected();
+    }
+
+    pub fn filter(&self) -> &str {
+        self.filter.as_ref().map_or("", |input| input.text())
+    }
+
+    /// Empties the filter and unfocuses it, so characters typed on another
+    /// screen are dropped instead of landing in the search on the next update.
+    pub fn reset_filter(&mut self) {
+        if let Some(input) = &mut self.filter {
+            input.set_text("");
+            input.update(false);
+            self.apply_filter();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filtered.is_empty()
+    }
+
+    fn apply_filter(&mut self) {
+        let needle = self.filter().to_lowercase();
+        self.filtered = self
+            .labels
+            .iter()
+            .enumerate()
+            .filter(|(_, label)| label.to_lowercase().contains(&needle))
+            .map(|(i, _)| i)
+            .collect();
+        if self.selected.is_some_and(|s| !self.filtered.contains(&s)) {
+            self.selected = None;
+        }
+        self.first_visible = 0;
+        self.scroll_to_selected();
+    }
+
+    fn items_rect(&self, rect: Rect) -> Rect {
+        if self.filter.is_some() {
+            let offset = FILTER_HEIGHT + self.spacing;
+            Rect::new(rect.x, rect.y + offset, rect.w, (rect.h - offset).max(0.0))
+        } else {
+            rect
+        }
+    }
+
+    fn visible_rows(&self, rect: Rect) -> usize {
+        let rows = ((self.items_rect(rect).h + self.spacing) / (self.item_height + self.spacing)).floor();
+        (rows as usize).max(1)
+    }
+
+    fn max_first_visible(&self, rect: Rect) -> usize {
+        self.filtered.len().saturating_sub(self.visible_rows(rect))
+    }
+
+    fn row_rect(&self, rect: Rect, row: usize) -> Rect {
+        let items = self.items_rect(rect);
+        let y = items.y + (row - self.first_visible) as f32 * (self.item_height + self.spacing);
+        Rect::new(items.x, y, items.w - SCROLLBAR_WIDTH * 2.0, self.item_height)
+    }
+
+    fn selected_row(&self) -> Option<usize> {
+        self.selected.and_then(|s| self.filtered.iter().position(|&i| i == s))
+    }
+
+    fn scroll_to_selected(&mut self) {
+        if let Some(row) = self.selected_row() {
+            if row < self.first_visible {
+                self.first_visible = row;
+            }
+        }
+    }
+
+    fn scroll_into_view(&mut self, rect: Rect) {
+        let rows = self.visible_rows(rect);
+        if let Some(row) = self.selected_row() {
+            if row < self.first_visible {
+                self.first_visible = row;
+            } else if row >= self.first_visible + rows {
+                self.first_visible = row + 1 - rows;
+            }
+        }
+        self.first_visible = self.first_visible.min(self.max_first_visible(rect));
+    }
+
+    fn move_selection(&mut self, delta: isize) -> ListEvent {
+        if self.filtered.is_empty() {
+            return ListEvent::None;
+        }
+        let row = match self.selected_row() {
+            Some(row) => (row as isize + delta).clamp(0, self.filtered.len() as isize - 1) as usize,
+            None => 0,
+        };
+        self.selected = Some(self.filtered[row]);
+        ListEvent::Selected(self.filtered[row])
+    }
+
+    fn update_filter(&mut self, rect: Rect, focused: bool) {
+        let Some(input) = &mut self.filter else {
+            return;
+        };
+        input.rect = Rect::new(rect.x, rect.y, rect.w, FILTER_HEIGHT);
+        let mut changed = input.update(focused);
+        if focused && is_key_pressed(KeyCode::Escape) && !input.text().is_empty() {
+            input.set_text("");
+            changed = true;
+        }
+        if changed {
+            self.apply_filter();
+        }
+    }
+
+    /// Keys only reach a `focused` list, so a focused button elsewhere gets
+    /// Enter and Space alone. While the filter has text, Home and End move
+    /// its caret instead of the selection.
+    pub fn update(&mut self, rect: Rect, focused: bool) -> ListEvent {
+        self.update_filter(rect, focused);
+
+        let mut event = ListEvent::None;
+        if focused {
+            event = self.update_keys(rect);
+            if let ListEvent::Activated(_) = event {
+                return event;
+            }
+        }
+        self.update_mouse(rect, event)
+    }
+
+    fn update_keys(&mut self, rect: Rect) -> ListEvent {
+        let mut event = ListEvent::None;
+        let rows = self.visible_rows(rect) as isize;
+        let typing = !self.filter().is_empty();
+        if is_key_pressed(KeyCode::Down) {
+            event = self.move_selection(1);
+        }
+        if is_key_pressed(KeyCode::Up) {
+            event = self.move_selection(-1);
+        }
+        if is_key_pressed(KeyCode::PageDown) {
+            event = self.move_selection(rows);
+        }
+        if is_key_pressed(KeyCode::PageUp) {
+            event = self.move_selection(-rows);
+        }
+        if !typing && is_key_pressed(KeyCode::Home) {
+            event = self.move_selection(-(self.filtered.len() as isize));
+        }
+        if !typing && is_key_pressed(KeyCode::End) {
+            event = self.move_selection(self.filtered.len() as isize);
+        }
+        if event != ListEvent::None {
+            self.scroll_into_view(rect);
+        }
+        if is_key_pressed(KeyCode::Enter) {
+            if let Some(selected) = self.selected {
+                return ListEvent::Activated(selected);
+            }
+        }
+        event
+    }
+
+    fn update_mouse(&mut self, rect: Rect, mut event: ListEvent) -> ListEvent {
+        let rows = self.visible_rows(rect);
+        let mouse = Vec2::from(mouse_position());
+        let items = self.items_rect(rect);
+        let wheel = mouse_wheel().1;
+        if wheel != 0.0 && items.contains(mouse) {
+            self.first_visible = if wheel > 0.0 {
+                self.first_visible.saturating_sub(1)
+            } else {
+                (self.first_visible + 1).min(self.max_first_visible(rect))
+            };
+        }
+
+        self.hovered = None;
+        let last = (self.first_visible + rows).min(self.filtered.len());
+        for row in self.first_visible..last {
+            if self.row_rect(rect, row).contains(mouse) {
+                let index = self.filtered[row];
+                self.hovered = Some(index);
+                if is_mouse_button_pressed(MouseButton::Left) {
+                    if self.selected == Some(index) {
+                        return ListEvent::Activated(index);
+                    }
+                    self.selected = Some(index);
+                    event = ListEvent::Selected(index);
+                }
+            }
+        }
+        event
+    }
+
+    /// Draws the filter field, the visible rows and a scrollbar.
+    /// `draw_item` receives the item index, its row rect and its state.
+    pub fn draw(&self, rect: Rect, mut draw_item: impl FnMut(usize, Rect, ItemState)) {
+        if let Some(input) = &self.filter {
+            input.draw();
+        }
+
+        let rows = self.visible_rows(rect);
+        let last = (self.first_visible + rows).min(self.filtered.len());
+        for row in self.first_visible..last {
+            let index = self.filtered[row];
+            let state = if self.selected == Some(index) {
+                ItemState::Selected
+            } else if self.hovered == Some(index) {
+                ItemState::Hovered
+            } else {
+                ItemState::Normal
+            };
+            draw_item(index, self.row_rect(rect, row), state);
+        }
+
+        if self.filtered.len() > rows {
+            let items = self.items_rect(rect);
+            let track_x = items.x + items.w - SCROLLBAR_WIDTH;
+            let thumb_h = (items.h * rows as f32 / self.filtered.len() as f32).max(12.0);
+            let max_first = self.max_first_visible(rect).max(1) as f32;
+            let thumb_y = items.y + (items.h - thumb_h) * self.first_visible as f32 / max_first;
+            draw_rectangle(track_x, items.y, SCROLLBAR_WIDTH, items.h, Color::new(1.0, 1.0, 1.0, 0.1));
+            draw_rectangle(track_x, thumb_y, SCROLLBAR_WIDTH, thumb_h, GRAY);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use macroquad::prelude::*;
+use crate::ui::style;
+
+pub struct Slider {
+    pub label: String,
+    pub rect: Rect,
+    value: f32,
+    min: f32,
+    max: f32,
+    step: f32,
+    dragging: bool,
+    hovered: bool,
+    focused: bool,
+}
+
+impl Slider {
+    pub fn new(label: &str, value: f32, min: f32, max: f32, step: f32) -> Self {
+        Self {
+            label: label.to_string(),
+            rect: Rect::default(),
+            value: value.clamp(min, max),
+            min,
+            max,
+            step,
+            dragging: false,
+            hovered: false,
+            focused: false,
+        }
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    fn track(&self) -> Rect {
+        let label_w = self.rect.w * 0.4;
+        Rect::new(self.rect.x + label_w, self.rect.y, self.rect.w - label_w, self.rect.h)
+    }
+
+    fn set_value(&mut self, value: f32) -> bool {
+        let snapped = ((value - self.min) / self.step).round() * self.step + self.min;
+        let snapped = snapped.clamp(self.min, self.max);
+        let changed = snapped != self.value;
+        self.value = snapped;
+        changed
+    }
+
+    /// Returns true when the value changed, by dragging or with Left/Right while focused.
+    pub fn update(&mut self, focused: bool) -> bool {
+        self.focused = focused;
+        let mouse = Vec2::from(mouse_position());
+        let track = self.track();
+        self.hovered = track.contains(mouse);
+        if self.hovered && is_mouse_button_pressed(MouseButton::Left) {
+            self.dragging = true;
+        }
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = false;
+        }
+        let mut changed = false;
+        if self.dragging {
+            let t = ((mouse.x - track.x) / track.w).clamp(0.0, 1.0);
+            changed |= self.set_value(self.min + t * (self.max - self.min));
+        }
+        if focused && is_key_pressed(KeyCode::Left) {
+            changed |= self.set_value(self.value - self.step);
+        }
+        if focused && is_key_pressed(KeyCode::Right) {
+            changed |= self.set_value(self.value + self.step);
+        }
+        changed
+    }
+
+    pub fn draw(&self) {
+        let color = if self.hovered || self.focused || self.dragging { style::HOVER_COLOR } else { style::TEXT_COLOR };
+        let font_size = self.rect.h * 0.8;
+        let text = format!("{}: {:.1}", self.label, self.value);
+        let dims = style::measure(&text, font_size);
+        style::draw_label(&text, self.rect.x, self.rect.y + (self.rect.h + dims.offset_y) / 2.0, font_size, color);
+
+        let track = self.track();
+        let mid_y = track.y + track.h / 2.0;
+        draw_line(track.x, mid_y, track.x + track.w, mid_y, 2.0, style::MUTED_COLOR);
+        let t = (self.value - self.min) / (self.max - self.min);
+        let knob_x = track.x + t * track.w;
+        draw_rectangle(knob_x - 5.0, track.y, 10.0, track.h, color);
+    }
+}
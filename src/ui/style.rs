@@ -0,0 +1,82 @@
+use gaymwtf_core::load_file_sync;
+use macroquad::prelude::*;
+use macroquad::text::{load_ttf_font_from_bytes, Font};
+use once_cell::sync::Lazy;
+use crate::assets::{self, TextureHandle};
+
+pub const TEXT_COLOR: Color = WHITE;
+pub const HOVER_COLOR: Color = YELLOW;
+pub const MUTED_COLOR: Color = GRAY;
+pub const ERROR_COLOR: Color = RED;
+pub const FIELD_COLOR: Color = DARKGRAY;
+
+static FONT: Lazy<Option<Font>> = Lazy::new(|| {
+    let font = load_file_sync("assets/bonspixels.ttf")
+        .and_then(|bytes| Ok(load_ttf_font_from_bytes(&bytes)?));
+    match font {
+        Ok(mut font) => {
+            font.set_filter(FilterMode::Nearest);
+            Some(font)
+        }
+        Err(e) => {
+            eprintln!("Failed to load UI font: {:?}", e);
+            None
+        }
+    }
+});
+
+static BUTTON_TEXTURES: Lazy<[TextureHandle; 3]> = Lazy::new(|| {
+    ["start", "middle", "end"].map(|part| assets::texture(&format!("assets/textures/gui/button_{}.png", part)))
+});
+
+pub fn font() -> Option<&'static Font> {
+    FONT.as_ref()
+}
+
+pub fn measure(text: &str, font_size: f32) -> TextDimensions {
+    measure_text(text, font(), font_size as u16, 1.0)
+}
+
+/// Draws `text` in the UI font with its baseline at `y`.
+pub fn draw_label(text: &str, x: f32, y: f32, font_size: f32, color: Color) {
+    draw_text_ex(text, x, y, TextParams {
+        font: font(),
+        font_size: font_size as u16,
+        color,
+        ..Default::default()
+    });
+}
+
+/// Draws `text` centered inside `rect`.
+pub fn draw_label_centered(text: &str, rect: Rect, font_size: f32, color: Color) {
+    let dims = measure(text, font_size);
+    draw_label(
+        text,
+        rect.x + (rect.w - dims.width) / 2.0,
+        rect.y + (rect.h + dims.offset_y) / 2.0 - dims.height * 0.1,
+        font_size,
+        color,
+    );
+}
+
+/// Draws the button frame from the start/middle/end textures: the caps keep
+/// their aspect ratio at the rect height and the middle stretches between them.
+pub fn draw_button_frame(rect: Rect, tint: Color) {
+    let [start, middle, end] = BUTTON_TEXTURES.map(assets::get);
+    let cap_w = (start.width() * rect.h / start.height()).min(rect.w / 2.0);
+    let draw = |texture: &Texture2D, x: f32, w: f32| {
+        draw_texture_ex(texture, x, rect.y, tint, DrawTextureParams {
+            dest_size: Some(vec2(w, rect.h)),
+            ..Default::default()
+        });
+    };
+    draw(&start, rect.x, cap_w);
+    draw(&middle, rect.x + cap_w, rect.w - cap_w * 2.0);
+    draw(&end, rect.x + rect.w - cap_w, cap_w);
+}
+
+pub fn draw_field(rect: Rect, focused: bool) {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, FIELD_COLOR);
+    let color = if focused { HOVER_COLOR } else { MUTED_COLOR };
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, color);
+}
@@ -0,0 +1,289 @@
+use macroquad::miniquad::window::{clipboard_get, clipboard_set};
+use macroquad::prelude::*;
+use crate::ui::style;
+
+/// Delay before a held key starts repeating, and the interval between repeats.
+const REPEAT_DELAY: f64 = 0.45;
+const REPEAT_INTERVAL: f64 = 0.035;
+const PADDING: f32 = 10.0;
+const SELECTION_COLOR: Color = Color::new(0.3, 0.45, 0.9, 0.6);
+
+/// A single-line text field with a caret, selection and clipboard support.
+///
+/// Positions (`caret`, `anchor`) count characters, not bytes, so any Unicode
+/// text the font can draw (Latin, Cyrillic, ...) edits correctly.
+pub struct TextInput {
+    pub rect: Rect,
+    pub font_size: f32,
+    text: String,
+    caret: usize,
+    /// The other end of the selection; the selection spans `anchor..caret`.
+    anchor: Option<usize>,
+    placeholder: String,
+    max_len: usize,
+    accept: fn(char) -> bool,
+    focused: bool,
+    /// The held key that is repeating and when it fires next.
+    repeat: Option<(KeyCode, f64)>,
+}
+
+impl TextInput {
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            rect: Rect::default(),
+            font_size: 28.0,
+            text: String::new(),
+            caret: 0,
+            anchor: None,
+            placeholder: String::new(),
+            max_len,
+            accept: |c| !c.is_control(),
+            focused: false,
+            repeat: None,
+        }
+    }
+
+    /// Restricts typed and pasted characters to those `accept` returns true for.
+    pub fn with_filter(mut self, accept: fn(char) -> bool) -> Self {
+        self.accept = accept;
+        self
+    }
+
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text and puts the caret at its end.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.chars().take(self.max_len).collect();
+        self.caret = self.len();
+        self.anchor = None;
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.text.char_indices().nth(char_index).map_or(self.text.len(), |(i, _)| i)
+    }
+
+    fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        (anchor != self.caret).then(|| (anchor.min(self.caret), anchor.max(self.caret)))
+    }
+
+    fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|(start, end)| &self.text[self.byte_index(start)..self.byte_index(end)])
+    }
+
+    fn delete_range(&mut self, start: usize, end: usize) {
+        let range = self.byte_index(start)..self.byte_index(end);
+        self.text.replace_range(range, "");
+        self.caret = start;
+        self.anchor = None;
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some((start, end)) => {
+                self.delete_range(start, end);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Inserts the accepted characters of `s` at the caret, replacing the selection.
+    fn insert(&mut self, s: &str) -> bool {
+        let mut changed = self.delete_selection();
+        for c in s.chars() {
+            if !(self.accept)(c) || c.is_control() || self.len() >= self.max_len {
+                continue;
+            }
+            let at = self.byte_index(self.caret);
+            self.text.insert(at, c);
+            self.caret += 1;
+            changed = true;
+        }
+        changed
+    }
+
+    fn move_caret(&mut self, to: usize, extend: bool) {
+        if extend {
+            self.anchor.get_or_insert(self.caret);
+        } else {
+            self.anchor = None;
+        }
+        self.caret = to.min(self.len());
+    }
+
+    /// True on the first press of `key` and then repeatedly while it is held.
+    fn fired(&mut self, key: KeyCode) -> bool {
+        let now = get_time();
+        if is_key_pressed(key) {
+            self.repeat = Some((key, now + REPEAT_DELAY));
+            return true;
+        }
+        match self.repeat {
+            Some((held, next)) if held == key && is_key_down(key) && now >= next => {
+                self.repeat = Some((key, now + REPEAT_INTERVAL));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The character index closest to screen position `x`.
+    fn index_at(&self, x: f32) -> usize {
+        let (start, _) = self.visible_range();
+        let mut best = start;
+        let mut best_distance = f32::MAX;
+        for i in start..=self.len() {
+            let distance = (self.x_of(start, i) - x).abs();
+            if distance < best_distance {
+                best = i;
+                best_distance = distance;
+            }
+        }
+        best
+    }
+
+    /// Screen x of the boundary before character `index`, when drawing starts at `start`.
+    fn x_of(&self, start: usize, index: usize) -> f32 {
+        let slice = &self.text[self.byte_index(start)..self.byte_index(index)];
+        self.rect.x + PADDING + style::measure(slice, self.font_size).width
+    }
+
+    /// The range of characters that fits in the field while keeping the caret visible.
+    fn visible_range(&self) -> (usize, usize) {
+        let max_x = self.rect.x + self.rect.w - PADDING;
+        let mut start = 0;
+        while start < self.caret && self.x_of(start, self.caret) > max_x {
+            start += 1;
+        }
+        let mut end = self.len();
+        while end > self.caret && self.x_of(start, end) > max_x {
+            end -= 1;
+        }
+        (start, end)
+    }
+
+    /// Returns true when the text changed. Only a focused input consumes typed characters.
+    pub fn update(&mut self, focused: bool) -> bool {
+        if focused && !self.focused {
+            while get_char_pressed().is_some() {}
+        }
+        self.focused = focused;
+        if !focused {
+            self.repeat = None;
+            return false;
+        }
+
+        let ctrl = is_key_down(KeyCode::LeftControl)
+            || is_key_down(KeyCode::RightControl)
+            || is_key_down(KeyCode::LeftSuper)
+            || is_key_down(KeyCode::RightSuper);
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        let mut changed = false;
+
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (x, y) = mouse_position();
+            if self.rect.contains(vec2(x, y)) {
+                let index = self.index_at(x);
+                self.move_caret(index, shift);
+            }
+        }
+
+        if ctrl {
+            if is_key_pressed(KeyCode::A) {
+                self.anchor = Some(0);
+                self.caret = self.len();
+            }
+            if is_key_pressed(KeyCode::C) || is_key_pressed(KeyCode::X) {
+                if let Some(selected) = self.selected_text() {
+                    clipboard_set(selected);
+                }
+                if is_key_pressed(KeyCode::X) {
+                    changed |= self.delete_selection();
+                }
+            }
+            if is_key_pressed(KeyCode::V) {
+                if let Some(pasted) = clipboard_get() {
+                    changed |= self.insert(&pasted);
+                }
+            }
+        }
+
+        while let Some(c) = get_char_pressed() {
+            if !ctrl {
+                let mut buf = [0; 4];
+                changed |= self.insert(c.encode_utf8(&mut buf));
+            }
+        }
+
+        if self.fired(KeyCode::Backspace) && !self.delete_selection() && self.caret > 0 {
+            self.delete_range(self.caret - 1, self.caret);
+            changed = true;
+        }
+        if self.fired(KeyCode::Delete) && !self.delete_selection() && self.caret < self.len() {
+            self.delete_range(self.caret, self.caret + 1);
+            changed = true;
+        }
+        if self.fired(KeyCode::Left) {
+            let to = match self.selection() {
+                Some((start, _)) if !shift => start,
+                _ => self.caret.saturating_sub(1),
+            };
+            self.move_caret(to, shift);
+        }
+        if self.fired(KeyCode::Right) {
+            let to = match self.selection() {
+                Some((_, end)) if !shift => end,
+                _ => self.caret + 1,
+            };
+            self.move_caret(to, shift);
+        }
+        if is_key_pressed(KeyCode::Home) {
+            self.move_caret(0, shift);
+        }
+        if is_key_pressed(KeyCode::End) {
+            self.move_caret(self.len(), shift);
+        }
+        changed
+    }
+
+    pub fn draw(&self) {
+        style::draw_field(self.rect, self.focused);
+        let baseline = self.rect.y + (self.rect.h + style::measure("Ag", self.font_size).offset_y) / 2.0;
+        let x = self.rect.x + PADDING;
+        if self.text.is_empty() && !self.placeholder.is_empty() {
+            style::draw_label(&self.placeholder, x, baseline, self.font_size, style::MUTED_COLOR);
+        }
+
+        let (start, end) = self.visible_range();
+        if let Some((sel_start, sel_end)) = self.selection() {
+            let from = self.x_of(start, sel_start.clamp(start, end));
+            let to = self.x_of(start, sel_end.clamp(start, end));
+            draw_rectangle(from, self.rect.y + 5.0, to - from, self.rect.h - 10.0, SELECTION_COLOR);
+        }
+        let visible = &self.text[self.byte_index(start)..self.byte_index(end)];
+        let color = if self.focused { style::HOVER_COLOR } else { style::TEXT_COLOR };
+        style::draw_label(visible, x, baseline, self.font_size, color);
+
+        if self.focused && (get_time() * 2.0) as i64 % 2 == 0 {
+            let caret_x = self.x_of(start, self.caret) + 1.0;
+            draw_line(caret_x, self.rect.y + 6.0, caret_x, self.rect.y + self.rect.h - 6.0, 2.0, style::HOVER_COLOR);
+        }
+    }
+}
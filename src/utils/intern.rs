@@ -0,0 +1,31 @@
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Core traits hand out `&'static str` ids, so names read from data files
+/// live for the rest of the program. Each distinct name is leaked once,
+/// however often the files are reloaded, so the leak is bounded by the tile,
+/// object and biome ids of the content that was ever loaded.
+pub fn intern(name: &str) -> &'static str {
+    static NAMES: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+    let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(&interned) = names.get(name) {
+        return interned;
+    }
+    let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+    names.insert(interned);
+    interned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_name_is_leaked_once() {
+        let first = intern(&String::from("intern-test"));
+        let again = intern(&String::from("intern-test"));
+        assert!(std::ptr::eq(first, again));
+        assert!(!std::ptr::eq(first, intern("intern-test-2")));
+    }
+}
@@ -0,0 +1,157 @@
+use gaymwtf_core::BiomeRegistry;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+
+use crate::content::Manifest;
+use crate::worldgen::spawn::find_spawn;
+use crate::worldgen::{GeneratorSettings, WorldGenerator};
+
+/// File in a world folder that holds its [`WorldMetadata`].
+pub const METADATA_FILE: &str = "gamestate.json";
+pub const GAME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GameMode {
+    #[default]
+    Survival,
+    Creative,
+}
+
+impl GameMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Survival => "Survival",
+            GameMode::Creative => "Creative",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            GameMode::Survival => GameMode::Creative,
+            GameMode::Creative => GameMode::Survival,
+        }
+    }
+}
+
+/// A named spot the player marked, in tile coordinates.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Waypoint {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Everything about a world that is not chunk data. Fields added after the
+/// first release default when missing, so every older save still loads.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WorldMetadata {
+    pub seed: u64,
+    /// The seed as the player typed it; empty for worlds made before text seeds.
+    #[serde(default)]
+    pub seed_text: String,
+    #[serde(flatten)]
+    pub generator: GeneratorSettings,
+    /// Tile the player (re)spawns on; older saves get one on first load.
+    #[serde(default)]
+    pub spawn: Option<(i32, i32)>,
+    /// Unix timestamps in seconds; 0 when unknown.
+    #[serde(default)]
+    pub created: u64,
+    #[serde(default)]
+    pub last_played: u64,
+    /// Real seconds spent in the world.
+    #[serde(default)]
+    pub play_time: f64,
+    /// Version of the game that last saved the world.
+    #[serde(default)]
+    pub game_version: String,
+    /// In-game time in seconds since the world was created.
+    #[serde(default)]
+    pub world_time: f64,
+    #[serde(default)]
+    pub game_mode: GameMode,
+    #[serde(default)]
+    pub waypoints: Vec<Waypoint>,
+    /// Every chunk the player has had loaded, shown on the world map.
+    #[serde(default)]
+    pub explored: BTreeSet<(i32, i32)>,
+    /// Ids of the mods the world was created with; all of them must be
+    /// installed to load it.
+    #[serde(default)]
+    pub mods: Vec<String>,
+    /// Display name, kept next to `world.json` so a repair can restore it;
+    /// empty until an older world is next saved.
+    #[serde(default)]
+    pub name: String,
+}
+
+impl WorldMetadata {
+    /// Metadata for a world created now with the current generator.
+    pub fn new(seed: u64, seed_text: &str, game_mode: GameMode) -> Self {
+        Self {
+            seed,
+            seed_text: seed_text.to_string(),
+            generator: GeneratorSettings::default(),
+            spawn: None,
+            created: crate::utils::saves::now_secs(),
+            last_played: 0,
+            play_time: 0.0,
+            game_version: GAME_VERSION.to_string(),
+            world_time: 0.0,
+            game_mode,
+            waypoints: Vec::new(),
+            explored: BTreeSet::new(),
+            mods: Vec::new(),
+            name: String::new(),
+        }
+    }
+
+    pub fn path(world_dir: &Path) -> PathBuf {
+        world_dir.join(METADATA_FILE)
+    }
+
+    pub fn load(world_dir: &Path) -> anyhow::Result<Self> {
+        let path = Self::path(world_dir);
+        let data = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&data).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Writes to a temporary file first and renames it over the old one, so a
+    /// crash mid-save never leaves a half-written file behind.
+    pub fn save(&mut self, world_dir: &Path) -> anyhow::Result<()> {
+        self.game_version = GAME_VERSION.to_string();
+        let path = Self::path(world_dir);
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn generator(&self) -> WorldGenerator {
+        WorldGenerator::from_settings(self.seed, &self.generator)
+    }
+
+    /// The stored spawn tile, searching for one first if the save has none.
+    pub fn spawn_point(&mut self, biome_registry: &BiomeRegistry, manifest: &Manifest) -> (i32, i32) {
+        if let Some(spawn) = self.spawn {
+            return spawn;
+        }
+        let spawn = find_spawn(&self.generator(), biome_registry, manifest);
+        self.spawn = Some(spawn);
+        spawn
+    }
+
+    /// The seed to show and copy: the original text if there is one.
+    pub fn seed_label(&self) -> String {
+        if self.seed_text.is_empty() {
+            self.seed.to_string()
+        } else {
+            self.seed_text.clone()
+        }
+    }
+}
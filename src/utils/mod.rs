@@ -1 +1,5 @@
-pub mod system;
\ No newline at end of file
+pub mod intern;
+pub mod metadata;
+pub mod saves;
+pub mod settings;
+pub mod system;
@@ -0,0 +1,390 @@
+use gaymwtf_core::WorldData;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::{bail, Context};
+
+use crate::utils::metadata::WorldMetadata;
+
+pub const SAVES_DIR: &str = "saves";
+pub const THUMBNAIL_FILE: &str = "thumbnail.png";
+pub const MAX_WORLD_NAME_LEN: usize = 32;
+const MAX_DIR_NAME_LEN: usize = 40;
+
+pub struct WorldEntry {
+    /// Display name from `world.json`; may contain any Unicode.
+    pub name: String,
+    /// Folder name under `saves/`, which identifies the world on disk.
+    pub dir_name: String,
+    pub path: PathBuf,
+    pub metadata: Option<WorldMetadata>,
+    pub last_played: u64,
+    pub play_time: f64,
+    /// `None` until [`WorldEntry::measure_size`] walks the folder.
+    pub size_on_disk: Option<u64>,
+}
+
+impl WorldEntry {
+    fn read(dir_name: String, path: PathBuf) -> Self {
+        let metadata = WorldMetadata::load(&path).ok();
+        let name = read_world_name(&path).unwrap_or_else(|| dir_name.clone());
+        Self {
+            size_on_disk: None,
+            path: fs::canonicalize(&path).unwrap_or(path),
+            last_played: metadata.as_ref().map_or(0, |m| m.last_played),
+            play_time: metadata.as_ref().map_or(0.0, |m| m.play_time),
+            metadata,
+            name,
+            dir_name,
+        }
+    }
+
+    /// Walks the world folder once and remembers its size.
+    pub fn measure_size(&mut self) -> u64 {
+        *self.size_on_disk.get_or_insert_with(|| dir_size(&self.path).unwrap_or(0))
+    }
+}
+
+/// The display name in the world's `world.json`, if it can be read.
+pub fn read_world_name(world_dir: &Path) -> Option<String> {
+    fs::read_to_string(world_dir.join("world.json"))
+        .ok()
+        .and_then(|data| serde_json::from_str::<WorldData>(&data).ok())
+        .map(|data| data.name)
+        .filter(|name| !name.trim().is_empty())
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+pub fn world_dir(dir_name: &str) -> PathBuf {
+    Path::new(SAVES_DIR).join(dir_name)
+}
+
+fn transliterate(c: char) -> Option<&'static str> {
+    let latin = match c.to_lowercase().next()? {
+        'а' => "a", 'б' => "b", 'в' => "v", 'г' => "g", 'д' => "d", 'е' => "e", 'ё' => "e",
+        'ж' => "zh", 'з' => "z", 'и' => "i", 'й' => "y", 'к' => "k", 'л' => "l", 'м' => "m",
+        'н' => "n", 'о' => "o", 'п' => "p", 'р' => "r", 'с' => "s", 'т' => "t", 'у' => "u",
+        'ф' => "f", 'х' => "h", 'ц' => "ts", 'ч' => "ch", 'ш' => "sh", 'щ' => "sch", 'ъ' => "",
+        'ы' => "y", 'ь' => "", 'э' => "e", 'ю' => "yu", 'я' => "ya", 'і' => "i", 'ї' => "yi",
+        'є' => "ye", 'ґ' => "g", 'ў' => "u",
+        _ => return None,
+    };
+    Some(latin)
+}
+
+/// Turns a display name into a folder name that is safe on every platform:
+/// lowercase ASCII letters, digits, `-` and `_`. Cyrillic is transliterated,
+/// everything else becomes `_`.
+pub fn sanitize_dir_name(name: &str) -> String {
+    let mut result = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            result.push(c.to_ascii_lowercase());
+        } else if let Some(latin) = transliterate(c) {
+            result.push_str(latin);
+        } else if !result.ends_with('_') {
+            result.push('_');
+        }
+    }
+    let mut result: String = result.trim_matches('_').chars().take(MAX_DIR_NAME_LEN).collect();
+    const RESERVED: [&str; 4] = ["con", "prn", "aux", "nul"];
+    let reserved = RESERVED.contains(&result.as_str())
+        || ((result.starts_with("com") || result.starts_with("lpt"))
+            && result.len() == 4
+            && result.ends_with(|c: char| c.is_ascii_digit()));
+    if reserved {
+        result.push('_');
+    }
+    if result.is_empty() {
+        result = "world".to_string();
+    }
+    result
+}
+
+/// A sanitized folder name for `name` that no existing world uses yet.
+pub fn unique_dir_name(name: &str) -> String {
+    unique_dir_name_in(Path::new(SAVES_DIR), name)
+}
+
+fn unique_dir_name_in(saves: &Path, name: &str) -> String {
+    let base = sanitize_dir_name(name);
+    let mut dir_name = base.clone();
+    let mut n = 2;
+    while saves.join(&dir_name).exists() {
+        dir_name = format!("{}-{}", base, n);
+        n += 1;
+    }
+    dir_name
+}
+
+/// Lists every world in `saves/`, most recently played first.
+pub fn list_worlds() -> Vec<WorldEntry> {
+    list_worlds_in(Path::new(SAVES_DIR))
+}
+
+fn list_worlds_in(saves: &Path) -> Vec<WorldEntry> {
+    let mut result = Vec::new();
+    if let Ok(entries) = fs::read_dir(saves) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                result.push(WorldEntry::read(entry.file_name().to_string_lossy().to_string(), path));
+            }
+        }
+    }
+    result.sort_by(|a, b| b.last_played.cmp(&a.last_played).then_with(|| a.name.cmp(&b.name)));
+    result
+}
+
+pub fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(path)?.flatten() {
+        let meta = entry.metadata()?;
+        total += if meta.is_dir() { dir_size(&entry.path())? } else { meta.len() };
+    }
+    Ok(total)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)?.flatten() {
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the display name to `world.json` and to the metadata's copy.
+fn write_world_name(dir: &Path, name: &str) -> anyhow::Result<()> {
+    let world_data = WorldData { name: name.to_string() };
+    fs::write(dir.join("world.json"), serde_json::to_string(&world_data)?)?;
+    if let Ok(mut metadata) = WorldMetadata::load(dir) {
+        metadata.name = name.to_string();
+        metadata.save(dir)?;
+    }
+    Ok(())
+}
+
+pub fn validate_world_name(name: &str) -> anyhow::Result<()> {
+    if name.trim().is_empty() {
+        bail!("World name cannot be empty");
+    }
+    if name.chars().count() > MAX_WORLD_NAME_LEN {
+        bail!("World name cannot be longer than {} characters", MAX_WORLD_NAME_LEN);
+    }
+    Ok(())
+}
+
+/// Changes the display name of a world. Its folder stays the same.
+pub fn rename_world(dir_name: &str, new_name: &str) -> anyhow::Result<()> {
+    rename_world_in(Path::new(SAVES_DIR), dir_name, new_name)
+}
+
+fn rename_world_in(saves: &Path, dir_name: &str, new_name: &str) -> anyhow::Result<()> {
+    validate_world_name(new_name)?;
+    write_world_name(&saves.join(dir_name), new_name.trim())
+        .with_context(|| format!("Failed to rename world \"{}\"", dir_name))
+}
+
+pub fn delete_world(dir_name: &str) -> anyhow::Result<()> {
+    delete_world_in(Path::new(SAVES_DIR), dir_name)
+}
+
+fn delete_world_in(saves: &Path, dir_name: &str) -> anyhow::Result<()> {
+    fs::remove_dir_all(saves.join(dir_name)).with_context(|| format!("Failed to delete world \"{}\"", dir_name))
+}
+
+/// Copies a world under the first free "<name> copy N" display name and
+/// returns the folder name of the copy. A copy that fails halfway is removed.
+pub fn duplicate_world(dir_name: &str) -> anyhow::Result<String> {
+    duplicate_world_in(Path::new(SAVES_DIR), dir_name)
+}
+
+fn duplicate_world_in(saves: &Path, dir_name: &str) -> anyhow::Result<String> {
+    let worlds = list_worlds_in(saves);
+    let name = worlds
+        .iter()
+        .find(|w| w.dir_name == dir_name)
+        .map_or(dir_name.to_string(), |w| w.name.clone());
+    let mut copy_name = format!("{} copy", name);
+    let mut n = 2;
+    while worlds.iter().any(|w| w.name == copy_name) {
+        copy_name = format!("{} copy {}", name, n);
+        n += 1;
+    }
+    let copy_dir_name = unique_dir_name_in(saves, &copy_name);
+    let new_dir = saves.join(&copy_dir_name);
+    let copied = copy_dir(&saves.join(dir_name), &new_dir)
+        .map_err(anyhow::Error::from)
+        .and_then(|()| write_world_name(&new_dir, &copy_name));
+    if let Err(e) = copied {
+        // Never leave a half-written copy behind in the world list.
+        let _ = fs::remove_dir_all(&new_dir);
+        return Err(e.context(format!("Failed to duplicate world \"{}\"", name)));
+    }
+    Ok(copy_dir_name)
+}
+
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0)
+    }
+}
+
+pub fn format_duration(secs: f64) -> String {
+    let secs = secs as u64;
+    if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Formats a Unix timestamp as a UTC `YYYY-MM-DD` date.
+pub fn format_date(timestamp: u64) -> String {
+    if timestamp == 0 {
+        return "unknown".to_string();
+    }
+    // Howard Hinnant's days-to-civil algorithm.
+    let days = (timestamp / 86400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+pub fn format_last_played(timestamp: u64) -> String {
+    if timestamp == 0 {
+        return "never".to_string();
+    }
+    let ago = now_secs().saturating_sub(timestamp);
+    if ago < 60 {
+        "just now".to_string()
+    } else if ago < 3600 {
+        format!("{} min ago", ago / 60)
+    } else if ago < 86400 {
+        format!("{} h ago", ago / 3600)
+    } else {
+        format!("{} days ago", ago / 86400)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn make_world(saves: &Path, dir_name: &str, name: &str) {
+        fs::create_dir_all(saves.join(dir_name).join("chunks")).unwrap();
+        fs::write(saves.join(dir_name).join("chunks/0_0.json"), "{}").unwrap();
+        write_world_name(&saves.join(dir_name), name).unwrap();
+    }
+
+    #[test]
+    fn dir_names_are_safe_on_every_platform() {
+        assert_eq!(sanitize_dir_name("My World!"), "my_world");
+        assert_eq!(sanitize_dir_name("Мой мир"), "moy_mir");
+        assert_eq!(sanitize_dir_name("Щука и Ёж"), "schuka_i_ezh");
+        assert_eq!(sanitize_dir_name("  --a/b\\c:d--  "), "--a_b_c_d--");
+        assert_eq!(sanitize_dir_name("日本"), "world");
+        assert_eq!(sanitize_dir_name("   "), "world");
+        assert_eq!(sanitize_dir_name("CON"), "con_");
+        assert_eq!(sanitize_dir_name("lpt1"), "lpt1_");
+        assert_eq!(sanitize_dir_name("console"), "console");
+        assert_eq!(sanitize_dir_name(&"x".repeat(100)).len(), MAX_DIR_NAME_LEN);
+    }
+
+    #[test]
+    fn dir_names_skip_existing_folders() {
+        let saves = TempDir::new().unwrap();
+        assert_eq!(unique_dir_name_in(saves.path(), "Мир"), "mir");
+        make_world(saves.path(), "mir", "Мир");
+        make_world(saves.path(), "mir-2", "МИР");
+        assert_eq!(unique_dir_name_in(saves.path(), "мир"), "mir-3");
+    }
+
+    #[test]
+    fn rename_keeps_the_folder() {
+        let saves = TempDir::new().unwrap();
+        make_world(saves.path(), "home", "Home");
+        rename_world_in(saves.path(), "home", "  Дом  ").unwrap();
+        assert_eq!(read_world_name(&saves.path().join("home")).as_deref(), Some("Дом"));
+        assert!(rename_world_in(saves.path(), "home", " ").is_err());
+        assert!(rename_world_in(saves.path(), "home", &"x".repeat(MAX_WORLD_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn duplicates_get_the_next_free_copy_name() {
+        let saves = TempDir::new().unwrap();
+        make_world(saves.path(), "home", "Home");
+        let first = duplicate_world_in(saves.path(), "home").unwrap();
+        let second = duplicate_world_in(saves.path(), "home").unwrap();
+        assert_eq!((first.as_str(), second.as_str()), ("home_copy", "home_copy_2"));
+        assert_eq!(read_world_name(&saves.path().join(&second)).as_deref(), Some("Home copy 2"));
+        assert!(saves.path().join(&second).join("chunks/0_0.json").exists());
+        let mut names: Vec<_> = list_worlds_in(saves.path()).into_iter().map(|w| w.name).collect();
+        names.sort();
+        assert_eq!(names, ["Home", "Home copy", "Home copy 2"]);
+    }
+
+    #[test]
+    fn failed_duplicates_leave_nothing_behind() {
+        let saves = TempDir::new().unwrap();
+        assert!(duplicate_world_in(saves.path(), "missing").is_err());
+        assert_eq!(fs::read_dir(saves.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn delete_removes_the_folder() {
+        let saves = TempDir::new().unwrap();
+        make_world(saves.path(), "home", "Home");
+        delete_world_in(saves.path(), "home").unwrap();
+        assert!(list_worlds_in(saves.path()).is_empty());
+        assert!(delete_world_in(saves.path(), "home").is_err());
+    }
+
+    #[test]
+    fn sizes_are_measured_once() {
+        let saves = TempDir::new().unwrap();
+        make_world(saves.path(), "home", "Home");
+        let mut world = list_worlds_in(saves.path()).pop().unwrap();
+        assert_eq!(world.size_on_disk, None);
+        let size = world.measure_size();
+        assert!(size > 2);
+        fs::write(saves.path().join("home/extra"), "more bytes").unwrap();
+        assert_eq!(world.measure_size(), size);
+    }
+
+    #[test]
+    fn formats_sizes_durations_and_dates() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_duration(125.9), "2m 5s");
+        assert_eq!(format_duration(7380.0), "2h 3m");
+        assert_eq!(format_date(0), "unknown");
+        assert_eq!(format_date(86_399), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_704_067_200), "2024-01-01");
+        assert_eq!(format_last_played(0), "never");
+        assert_eq!(format_last_played(now_secs()), "just now");
+        assert_eq!(format_last_played(now_secs() - 3 * 86400), "3 days ago");
+    }
+}
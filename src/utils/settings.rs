@@ -0,0 +1,47 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Game-wide settings, relative to the working directory.
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Settings that outlive a single world.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Enabled resource packs by folder name; earlier packs win.
+    pub resource_packs: Vec<String>,
+}
+
+impl Settings {
+    /// Reads `path`; a missing file gives the defaults and a broken one is
+    /// reported and replaced by them, so settings never stop the game.
+    pub fn load_from(path: &Path) -> Self {
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(_) => return Self::default(),
+        };
+        serde_json::from_str(&data).unwrap_or_else(|e| {
+            eprintln!("Ignoring broken {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(Path::new(SETTINGS_FILE))
+    }
+
+    /// Writes through a temporary file, like world metadata.
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        self.save_to(Path::new(SETTINGS_FILE))
+    }
+}
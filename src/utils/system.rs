@@ -13,6 +13,7 @@ pub enum SystemError {
 }
 
 pub struct SystemInfo {
+    /// Resident memory of the game process in megabytes.
     pub process_memory: u32,
     pub cpu_usage: f32,
     pub fps: u32,              
@@ -21,7 +22,12 @@ pub struct SystemInfo {
     receiver: mpsc::Receiver<Result<(u32, f32), SystemError>>,
     last_system_update: f64, 
     cpu_count: usize,        
-    show_debug: bool,
+}
+
+impl Default for SystemInfo {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SystemInfo {
@@ -57,18 +63,9 @@ impl SystemInfo {
             receiver,
             last_system_update: get_time(),
             cpu_count,
-            show_debug: false,
         }
     }
 
-    pub fn toggle_debug(&mut self) {
-        self.show_debug = !self.show_debug;
-    }
-
-    pub fn is_debug_visible(&self) -> bool {
-        self.show_debug
-    }
-
     pub fn update(&mut self) {
         self.frames_count += 1;
         let current_time = get_time();
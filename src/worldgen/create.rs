@@ -0,0 +1,57 @@
+use gaymwtf_core::World;
+use std::path::Path;
+use anyhow::Context;
+
+use crate::utils::metadata::{GameMode, WorldMetadata};
+use crate::utils::saves;
+use crate::worldgen::seed::{parse_seed, random_seed};
+use crate::worldgen::spawn::{find_spawn, spawn_chunk, spawn_position};
+use crate::worldgen::generate_chunk;
+
+/// Creates a new save with the spawn chunk and a player, ready to be loaded.
+/// The folder name is derived from `name`; it is returned for opening the world.
+/// `seed_text` is what the player typed; an empty seed picks a random one.
+/// The folder is only made once the world is built, and removed again if
+/// saving fails, so a failed attempt leaves no half-made world behind.
+pub fn create_world(name: &str, seed_text: &str, game_mode: GameMode) -> anyhow::Result<String> {
+    saves::validate_world_name(name)?;
+    let name = name.trim();
+
+    let seed_text = seed_text.trim();
+    let seed = if seed_text.is_empty() { random_seed() } else { parse_seed(seed_text) };
+    let mut metadata = WorldMetadata::new(seed, seed_text, game_mode);
+    metadata.name = name.to_string();
+    metadata.mods = crate::mods::installed_ids()?;
+    let content = crate::mods::content_for_world(&metadata)?;
+    let generator = metadata.generator();
+    let (tile_registry, object_registry, biome_registry) = crate::init_registries(&content)?;
+    let spawn = find_spawn(&generator, &biome_registry, &content.manifest);
+    metadata.spawn = Some(spawn);
+    let mut world = World::new(name, tile_registry, object_registry, biome_registry);
+    let mut initial_chunk = futures::executor::block_on(generate_chunk(
+        spawn_chunk(spawn),
+        &generator,
+        &world.tile_registry,
+        &world.object_registry,
+        &world.biome_registry,
+    ))?;
+    if let Some(mut player) = world.object_registry.create_object_by_id("player") {
+        player.set_pos(spawn_position(spawn));
+        initial_chunk.objects.push(player);
+    }
+    world.add_chunk(initial_chunk);
+
+    let dir_name = saves::unique_dir_name(name);
+    let save_dir = format!("saves/{}", dir_name);
+    std::fs::create_dir_all(&save_dir).with_context(|| format!("Error creating folder {}", save_dir))?;
+    let saved = world
+        .save_world(&save_dir)
+        .map_err(|e| anyhow::anyhow!(e))
+        .with_context(|| format!("Failed to save world \"{}\"", name))
+        .and_then(|()| metadata.save(Path::new(&save_dir)));
+    if let Err(e) = saved {
+        let _ = std::fs::remove_dir_all(&save_dir);
+        return Err(e);
+    }
+    Ok(dir_name)
+}
@@ -0,0 +1,172 @@
+use gaymwtf_core::{BiomeRegistry, CHUNK_SIZE};
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use crate::content::Manifest;
+use crate::worldgen::spawn::find_spawn;
+use crate::worldgen::{GeneratorSettings, WorldGenerator};
+
+/// Biome that forms rivers and lakes.
+const RIVER_BIOME: &str = "river";
+/// Largest search radius in chunks. Every seed checked samples a square of
+/// `(2 * radius + 1)` chunks a side, so memory and time grow with its square.
+pub const MAX_RADIUS: i32 = 16;
+
+/// A condition a seed has to meet to be reported.
+#[derive(Debug, Clone)]
+pub enum Criterion {
+    /// The spawn tile is walkable ground rather than water.
+    SpawnOnLand,
+    /// A biome matching `name` lies within `radius` chunks of the spawn.
+    /// A name matches its own tag and every tag it prefixes, so `snow`
+    /// matches both `snow_plains` and `snow_forest`.
+    BiomeNearSpawn { name: String, radius: i32 },
+    /// A connected river within `radius` chunks of the spawn spans at least
+    /// `min_length` tiles along its longer side.
+    RiverLength { min_length: usize, radius: i32 },
+}
+
+impl Criterion {
+    fn radius(&self) -> i32 {
+        match self {
+            Criterion::SpawnOnLand => 0,
+            Criterion::BiomeNearSpawn { radius, .. } | Criterion::RiverLength { radius, .. } => *radius,
+        }
+    }
+}
+
+fn biome_matches(name: &str, tag: &str) -> bool {
+    tag == name || tag.strip_prefix(name).is_some_and(|rest| rest.starts_with('_'))
+}
+
+/// A seed that met every criterion.
+#[derive(Debug, Clone)]
+pub struct SeedMatch {
+    pub seed: u64,
+    pub spawn: (i32, i32),
+    pub spawn_biome: &'static str,
+    /// Longest river found near the spawn, if any criterion looked for one.
+    pub river_length: Option<usize>,
+}
+
+/// Biome tags of every tile in a square of chunks around the spawn.
+struct BiomeGrid {
+    side: usize,
+    tags: Vec<Option<&'static str>>,
+}
+
+impl BiomeGrid {
+    fn new(generator: &WorldGenerator, biome_registry: &BiomeRegistry, spawn: (i32, i32), radius: i32) -> Self {
+        let chunk_size = CHUNK_SIZE as i32;
+        let spawn_chunk = (spawn.0.div_euclid(chunk_size), spawn.1.div_euclid(chunk_size));
+        let origin = ((spawn_chunk.0 - radius) * chunk_size, (spawn_chunk.1 - radius) * chunk_size);
+        let side = (2 * radius + 1) as usize * CHUNK_SIZE;
+        let mut tags = Vec::with_capacity(side * side);
+        for y in 0..side as i32 {
+            for x in 0..side as i32 {
+                let (height, moisture, temperature) = generator.get_values(origin.0 + x, origin.1 + y);
+                tags.push(biome_registry.find_biome(height, moisture, temperature).map(|b| b.get_type_tag()));
+            }
+        }
+        Self { side, tags }
+    }
+
+    /// Tile rows (and columns) that lie within `radius` chunks of the spawn chunk.
+    fn bounds(&self, radius: i32) -> Range<usize> {
+        let full_radius = (self.side / CHUNK_SIZE) as i32 / 2;
+        let skip = (full_radius - radius) as usize * CHUNK_SIZE;
+        skip..self.side - skip
+    }
+
+    fn cells(&self, radius: i32) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let range = self.bounds(radius);
+        range.clone().flat_map(move |y| range.clone().map(move |x| (x, y)))
+    }
+
+    fn tag(&self, x: usize, y: usize) -> Option<&'static str> {
+        self.tags[y * self.side + x]
+    }
+
+    fn contains_biome(&self, name: &str, radius: i32) -> bool {
+        self.cells(radius)
+            .any(|(x, y)| self.tag(x, y).is_some_and(|tag| biome_matches(name, tag)))
+    }
+
+    /// Longer bounding-box side, in tiles, of the largest connected river
+    /// within `radius` chunks of the spawn.
+    fn longest_river(&self, radius: i32) -> usize {
+        let bounds = self.bounds(radius);
+        let mut visited = vec![false; self.tags.len()];
+        let mut longest = 0;
+        for (x, y) in self.cells(radius) {
+            if visited[y * self.side + x] || self.tag(x, y) != Some(RIVER_BIOME) {
+                continue;
+            }
+            let (mut min, mut max) = ((x, y), (x, y));
+            let mut queue = VecDeque::from([(x, y)]);
+            visited[y * self.side + x] = true;
+            while let Some((cx, cy)) = queue.pop_front() {
+                min = (min.0.min(cx), min.1.min(cy));
+                max = (max.0.max(cx), max.1.max(cy));
+                let neighbours = [
+                    (cx.wrapping_sub(1), cy),
+                    (cx + 1, cy),
+                    (cx, cy.wrapping_sub(1)),
+                    (cx, cy + 1),
+                ];
+                for (nx, ny) in neighbours {
+                    if bounds.contains(&nx)
+                        && bounds.contains(&ny)
+                        && !visited[ny * self.side + nx]
+                        && self.tag(nx, ny) == Some(RIVER_BIOME)
+                    {
+                        visited[ny * self.side + nx] = true;
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+            longest = longest.max((max.0 - min.0 + 1).max(max.1 - min.1 + 1));
+        }
+        longest
+    }
+}
+
+/// Checks one seed against `criteria`, cheapest checks first.
+pub fn check_seed(
+    seed: u64,
+    settings: &GeneratorSettings,
+    biome_registry: &BiomeRegistry,
+    manifest: &Manifest,
+    criteria: &[Criterion],
+) -> Option<SeedMatch> {
+    let generator = WorldGenerator::from_settings(seed, settings);
+    let spawn = find_spawn(&generator, biome_registry, manifest);
+    let (height, moisture, temperature) = generator.get_values(spawn.0, spawn.1);
+    let spawn_biome = biome_registry.find_biome(height, moisture, temperature)?;
+    let on_land = manifest.is_walkable(spawn_biome.get_ground_tile_type());
+    if !on_land && criteria.iter().any(|c| matches!(c, Criterion::SpawnOnLand)) {
+        return None;
+    }
+
+    let radius = criteria.iter().map(Criterion::radius).max().unwrap_or(0);
+    let grid = BiomeGrid::new(&generator, biome_registry, spawn, radius);
+    let mut river_length = None;
+    for criterion in criteria {
+        match criterion {
+            Criterion::SpawnOnLand => {}
+            Criterion::BiomeNearSpawn { name, radius } => {
+                if !grid.contains_biome(name, *radius) {
+                    return None;
+                }
+            }
+            Criterion::RiverLength { min_length, radius } => {
+                let length = grid.longest_river(*radius);
+                river_length = Some(river_length.unwrap_or(0).max(length));
+                if length < *min_length {
+                    return None;
+                }
+            }
+        }
+    }
+    Some(SeedMatch { seed, spawn, spawn_biome: spawn_biome.get_type_tag(), river_length })
+}
@@ -1,50 +1,158 @@
-use gaymwtf_core::{BiomeRegistry, Chunk, ObjectRegistry, TileRegistry, CHUNK_SIZE, TILE_SIZE};
+use gaymwtf_core::{Biome, BiomeRegistry, Chunk, ObjectRegistry, TileRegistry, CHUNK_SIZE, TILE_SIZE};
 use macroquad::prelude::*;
 use noise::{NoiseFn, Perlin};
-use ::rand::rng;
-use ::rand::Rng;
+use serde::{Deserialize, Serialize};
 
-pub struct WorldGenerator {
+use crate::worldgen::seed::mix64;
+
+/// Version of the terrain algorithm a world was created with. Saves made before
+/// 64-bit seeds existed keep generating with the old algorithm so new chunks
+/// still line up with the ones already on disk.
+pub const GENERATOR_VERSION: u32 = 2;
+pub const LEGACY_GENERATOR_VERSION: u32 = 1;
+
+fn legacy_generator_version() -> u32 {
+    LEGACY_GENERATOR_VERSION
+}
+
+fn default_scale() -> f64 {
+    0.003
+}
+
+fn default_octaves() -> usize {
+    6
+}
+
+fn default_persistence() -> f64 {
+    0.8
+}
+
+fn default_lacunarity() -> f64 {
+    2.0
+}
+
+/// Terrain parameters stored with each world. A save without them is a
+/// legacy world with the original noise parameters.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GeneratorSettings {
+    #[serde(rename = "generator_version", default = "legacy_generator_version")]
+    pub version: u32,
+    #[serde(rename = "noise_scale", default = "default_scale")]
+    pub scale: f64,
+    #[serde(default = "default_octaves")]
+    pub octaves: usize,
+    #[serde(default = "default_persistence")]
+    pub persistence: f64,
+    #[serde(default = "default_lacunarity")]
+    pub lacunarity: f64,
+}
+
+impl Default for GeneratorSettings {
+    /// Settings for a world created now.
+    fn default() -> Self {
+        Self {
+            version: GENERATOR_VERSION,
+            scale: default_scale(),
+            octaves: default_octaves(),
+            persistence: default_persistence(),
+            lacunarity: default_lacunarity(),
+        }
+    }
+}
+
+struct NoiseLayer {
     perlin: Perlin,
+    offset: (f64, f64),
+}
+
+impl NoiseLayer {
+    /// Derives the layer's permutation and offset from the whole 64-bit seed,
+    /// salted so every layer gets an independent noise field.
+    fn new(seed: u64, salt: u64) -> Self {
+        let hash = mix64(seed ^ salt);
+        let offset_bits = mix64(hash);
+        // Perlin repeats every 256 units, so a fractional offset in that range
+        // is what actually shifts the field.
+        let to_offset = |bits: u64| (bits as f64 / u32::MAX as f64) * 256.0;
+        Self {
+            perlin: Perlin::new((hash ^ (hash >> 32)) as u32),
+            offset: (to_offset(offset_bits >> 32), to_offset(offset_bits & 0xFFFF_FFFF)),
+        }
+    }
+}
+
+pub struct WorldGenerator {
+    seed: u64,
+    height: NoiseLayer,
+    moisture: NoiseLayer,
+    temperature: NoiseLayer,
     scale: f64,
     octaves: usize,
     persistence: f64,
     lacunarity: f64,
-    height_offset: (f64, f64),
-    moisture_offset: (f64, f64),
-    temp_offset: (f64, f64),
 }
 
 impl WorldGenerator {
-    pub fn new(seed: u32) -> Self {
-        let seed = seed as u64;
-        
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            height: NoiseLayer::new(seed, 0x6865_6967_6874),
+            moisture: NoiseLayer::new(seed, 0x6D6F_6973_7475),
+            temperature: NoiseLayer::new(seed, 0x7465_6D70_6572),
+            scale: default_scale(),
+            octaves: default_octaves(),
+            persistence: default_persistence(),
+            lacunarity: default_lacunarity(),
+        }
+    }
+
+    /// The generator used before 64-bit seeds: one permutation for every
+    /// layer, with offsets derived from the low 32 bits of the seed.
+    pub fn legacy(seed: u32) -> Self {
+        let seed64 = seed as u64;
+        let layer = |offset: (f64, f64)| NoiseLayer { perlin: Perlin::new(seed), offset };
+        Self {
+            seed: seed64,
+            height: layer((0.0, 0.0)),
+            moisture: layer((
+                (seed64.wrapping_mul(12345) % 100000) as f64,
+                (seed64.wrapping_mul(54321) % 100000) as f64,
+            )),
+            temperature: layer((
+                (seed64.wrapping_mul(67890) % 100000) as f64,
+                (seed64.wrapping_mul(9876) % 100000) as f64,
+            )),
+            scale: default_scale(),
+            octaves: default_octaves(),
+            persistence: default_persistence(),
+            lacunarity: default_lacunarity(),
+        }
+    }
+
+    pub fn from_settings(seed: u64, settings: &GeneratorSettings) -> Self {
+        let generator = if settings.version <= LEGACY_GENERATOR_VERSION {
+            Self::legacy(seed as u32)
+        } else {
+            Self::new(seed)
+        };
         Self {
-            perlin: Perlin::new(seed as u32),
-            scale: 0.003,
-            octaves: 6,
-            persistence: 0.8,
-            lacunarity: 2.0,
-            height_offset: (0.0, 0.0),
-            moisture_offset: (
-                (seed.wrapping_mul(12345) % 100000) as f64,
-                (seed.wrapping_mul(54321) % 100000) as f64,
-            ),
-            temp_offset: (
-                (seed.wrapping_mul(67890) % 100000) as f64,
-                (seed.wrapping_mul(9876) % 100000) as f64,
-            ),
+            scale: settings.scale,
+            octaves: settings.octaves,
+            persistence: settings.persistence,
+            lacunarity: settings.lacunarity,
+            ..generator
         }
     }
 
-    fn generate_noise(&self, x: f64, y: f64) -> f64 {
+    fn generate_noise(&self, layer: &NoiseLayer, x: f64, y: f64) -> f64 {
+        let (x, y) = (x + layer.offset.0, y + layer.offset.1);
         let mut total = 0.0;
         let mut frequency = 1.0;
         let mut amplitude = 1.0;
         let mut max_amplitude = 0.0;
 
         for _ in 0..self.octaves {
-            total += self.perlin.get([x * frequency, y * frequency]) * amplitude;
+            total += layer.perlin.get([x * frequency, y * frequency]) * amplitude;
             max_amplitude += amplitude;
             amplitude *= self.persistence;
             frequency *= self.lacunarity;
@@ -53,13 +161,32 @@ impl WorldGenerator {
         total / max_amplitude
     }
 
+    /// A uniform value in `0..1` that depends only on the seed, the tile and `salt`.
+    fn roll(&self, world_x: i32, world_y: i32, salt: u64) -> f32 {
+        let position = ((world_x as u32 as u64) << 32) | world_y as u32 as u64;
+        let hash = mix64(self.seed ^ mix64(position) ^ salt.wrapping_mul(0xA24B_AED4_963E_E407));
+        (hash >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// The objects `biome` places on a tile. The same seed always gives the
+    /// same objects, so spawn checks and previews agree with generated chunks.
+    pub fn objects_at(&self, biome: &dyn Biome, world_x: i32, world_y: i32) -> Vec<&'static str> {
+        biome
+            .get_spawnable_objects()
+            .into_iter()
+            .enumerate()
+            .filter(|(i, (_, chance))| self.roll(world_x, world_y, *i as u64) < *chance)
+            .map(|(_, (object_type, _))| object_type)
+            .collect()
+    }
+
     pub fn get_values(&self, world_x: i32, world_y: i32) -> (f64, f64, f64) {
         let nx = self.scale * world_x as f64;
         let ny = self.scale * world_y as f64;
 
-        let height = (self.generate_noise(nx + self.height_offset.0, ny + self.height_offset.1) + 1.0) / 2.0;
-        let temp = (self.generate_noise(nx + self.temp_offset.0, ny + self.temp_offset.1) + 1.0) / 2.0;
-        let moist = (self.generate_noise(nx + self.moisture_offset.0, ny + self.moisture_offset.1) + 1.0) / 2.0;
+        let height = (self.generate_noise(&self.height, nx, ny) + 1.0) / 2.0;
+        let temp = (self.generate_noise(&self.temperature, nx, ny) + 1.0) / 2.0;
+        let moist = (self.generate_noise(&self.moisture, nx, ny) + 1.0) / 2.0;
 
         (height, moist, temp)
     }
@@ -67,15 +194,13 @@ impl WorldGenerator {
 
 pub async fn generate_chunk(
     chunk_pos: (i32, i32),
-    seed: u32,
+    generator: &WorldGenerator,
     tile_registry: &TileRegistry,
     object_registry: &ObjectRegistry,
     biome_registry: &BiomeRegistry,
 ) -> anyhow::Result<Chunk> {
-    let generator = WorldGenerator::new(seed);
     let mut tiles = Vec::new();
     let mut objects = Vec::new();
-    let mut rng = rng();
 
     for y in 0..CHUNK_SIZE {
         for x in 0..CHUNK_SIZE {
@@ -91,12 +216,10 @@ pub async fn generate_chunk(
                     tiles.push(tile);
                 }
 
-                for (object_type, chance) in biome.get_spawnable_objects() {
-                    if rng.random::<f32>() < chance {
-                        if let Some(mut obj) = object_registry.create_object_by_id(object_type) {
-                            obj.set_pos(tile_pos);
-                            objects.push(obj);
-                        }
+                for object_type in generator.objects_at(biome, world_x, world_y) {
+                    if let Some(mut obj) = object_registry.create_object_by_id(object_type) {
+                        obj.set_pos(tile_pos);
+                        objects.push(obj);
                     }
                 }
             }
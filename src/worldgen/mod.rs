@@ -1,2 +1,10 @@
+pub mod create;
+pub mod finder;
 pub mod generator;
-pub use generator::{generate_chunk, WorldGenerator};
\ No newline at end of file
+pub mod preview;
+pub mod repair;
+pub mod seed;
+pub mod spawn;
+pub use create::create_world;
+pub use generator::{generate_chunk, GeneratorSettings, WorldGenerator};
+pub use repair::{repair_world, RepairReport};
\ No newline at end of file
@@ -0,0 +1,111 @@
+use gaymwtf_core::{BiomeRegistry, CHUNK_SIZE};
+use image::{Rgba, RgbaImage};
+use std::path::Path;
+
+use crate::worldgen::WorldGenerator;
+
+pub const THUMBNAIL_SIZE: u32 = 64;
+/// World tiles covered by one thumbnail pixel.
+pub const THUMBNAIL_SCALE: i32 = 4;
+
+pub fn biome_color(tag: &str) -> [u8; 4] {
+    match tag {
+        "river" => [52, 101, 164, 255],
+        "beach" => [237, 212, 140, 255],
+        "desert" => [219, 186, 98, 255],
+        "plains" => [112, 178, 72, 255],
+        "forest" => [47, 118, 52, 255],
+        "snow_plains" => [226, 236, 242, 255],
+        "snow_forest" => [164, 190, 196, 255],
+        // Loud on purpose: fallback tiles mean the biome ranges have a gap.
+        "fallback" => [255, 0, 255, 255],
+        _ => [0, 0, 0, 255],
+    }
+}
+
+pub fn object_color(tag: &str) -> [u8; 4] {
+    match tag {
+        "tree" => [24, 72, 28, 255],
+        "snow_tree" => [88, 120, 110, 255],
+        "cactus" => [76, 140, 60, 255],
+        _ => [255, 0, 255, 255],
+    }
+}
+
+/// Renders every tile of the chunks from `from` to `to` (both inclusive) as a
+/// `tile_pixels`-wide square coloured by its biome. With `objects`, tiles that
+/// hold an object get a smaller square in the object's colour on top.
+pub fn render_chunk_map(
+    generator: &WorldGenerator,
+    biome_registry: &BiomeRegistry,
+    from: (i32, i32),
+    to: (i32, i32),
+    tile_pixels: u32,
+    objects: bool,
+) -> RgbaImage {
+    let chunk_size = CHUNK_SIZE as i32;
+    let (min_x, max_x) = (from.0.min(to.0), from.0.max(to.0));
+    let (min_y, max_y) = (from.1.min(to.1), from.1.max(to.1));
+    let width = (max_x - min_x + 1) as u32 * CHUNK_SIZE as u32;
+    let height = (max_y - min_y + 1) as u32 * CHUNK_SIZE as u32;
+    let margin = if tile_pixels >= 3 { tile_pixels / 4 } else { 0 };
+
+    let mut image = RgbaImage::new(width * tile_pixels, height * tile_pixels);
+    for ty in 0..height {
+        for tx in 0..width {
+            let world_x = min_x * chunk_size + tx as i32;
+            let world_y = min_y * chunk_size + ty as i32;
+            let (h, moisture, temperature) = generator.get_values(world_x, world_y);
+            let biome = biome_registry.find_biome(h, moisture, temperature);
+            let ground = biome.map_or([0, 0, 0, 255], |biome| biome_color(biome.get_type_tag()));
+            let object = biome
+                .filter(|_| objects)
+                .and_then(|biome| generator.objects_at(biome, world_x, world_y).first().copied())
+                .map(object_color);
+
+            for py in 0..tile_pixels {
+                for px in 0..tile_pixels {
+                    let inner = (margin..tile_pixels - margin).contains(&px) && (margin..tile_pixels - margin).contains(&py);
+                    let color = match object {
+                        Some(color) if inner => color,
+                        _ => ground,
+                    };
+                    image.put_pixel(tx * tile_pixels + px, ty * tile_pixels + py, Rgba(color));
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Renders a `size`x`size` image with one pixel per `scale` tiles, coloured
+/// by the biome at that spot and centered on tile `center`.
+pub fn render_biome_map(
+    generator: &WorldGenerator,
+    biome_registry: &BiomeRegistry,
+    center: (i32, i32),
+    size: u32,
+    scale: i32,
+) -> RgbaImage {
+    let half = size as i32 / 2;
+    RgbaImage::from_fn(size, size, |px, py| {
+        let world_x = center.0 + (px as i32 - half) * scale;
+        let world_y = center.1 + (py as i32 - half) * scale;
+        let (height, moisture, temperature) = generator.get_values(world_x, world_y);
+        let color = biome_registry
+            .find_biome(height, moisture, temperature)
+            .map_or([0, 0, 0, 255], |biome| biome_color(biome.get_type_tag()));
+        Rgba(color)
+    })
+}
+
+pub fn save_thumbnail(
+    generator: &WorldGenerator,
+    biome_registry: &BiomeRegistry,
+    center: (i32, i32),
+    path: &Path,
+) -> anyhow::Result<RgbaImage> {
+    let image = render_biome_map(generator, biome_registry, center, THUMBNAIL_SIZE, THUMBNAIL_SCALE);
+    image.save(path)?;
+    Ok(image)
+}
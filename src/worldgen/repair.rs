@@ -0,0 +1,131 @@
+use gaymwtf_core::{Chunk, WorldData, CHUNK_SIZE};
+use macroquad::prelude::*;
+use std::fs;
+use std::path::Path;
+use anyhow::Context;
+
+use crate::utils::metadata::WorldMetadata;
+use crate::worldgen::generate_chunk;
+use crate::worldgen::spawn::{spawn_chunk, spawn_position};
+
+#[derive(Debug, Default)]
+pub struct RepairReport {
+    pub restored_world_file: bool,
+    pub regenerated_chunks: Vec<(i32, i32)>,
+    pub skipped_files: Vec<String>,
+    pub restored_player: bool,
+}
+
+impl RepairReport {
+    /// One line for the player saying what was fixed.
+    pub fn summary(&self) -> String {
+        let mut fixed = Vec::new();
+        if self.restored_world_file {
+            fixed.push("restored world.json".to_string());
+        }
+        if !self.regenerated_chunks.is_empty() {
+            fixed.push(format!("regenerated {} chunk(s)", self.regenerated_chunks.len()));
+        }
+        if self.restored_player {
+            fixed.push("placed a new player at spawn".to_string());
+        }
+        if !self.skipped_files.is_empty() {
+            fixed.push(format!("ignored {} unknown file(s)", self.skipped_files.len()));
+        }
+        if fixed.is_empty() {
+            "World repaired: nothing was broken".to_string()
+        } else {
+            format!("World repaired: {}", fixed.join(", "))
+        }
+    }
+}
+
+fn parse_chunk_file_name(file_name: &str) -> Option<(i32, i32)> {
+    let coords = file_name.strip_prefix("chunk_")?.strip_suffix(".json")?;
+    let (x, y) = coords.rsplit_once('_')?;
+    Some((x.parse().ok()?, y.parse().ok()?))
+}
+
+/// Regenerates missing or corrupt parts of a save from its seed.
+///
+/// Chunks that fail to deserialize or have an incomplete tile set are
+/// regenerated in place, a missing `world.json` is recreated and a new
+/// player is placed on the spawn chunk if none survived.
+pub fn repair_world(world_name: &str) -> anyhow::Result<RepairReport> {
+    let save_dir = format!("saves/{}", world_name);
+    let mut metadata = WorldMetadata::load(Path::new(&save_dir)).context("Cannot repair without the world seed")?;
+    let generator = metadata.generator();
+    let content = crate::mods::content_for_world(&metadata)?;
+    let (tile_registry, object_registry, biome_registry) = crate::init_registries(&content)?;
+    let mut report = RepairReport::default();
+
+    let world_data_path = format!("{}/world.json", save_dir);
+    let world_data_ok = fs::read_to_string(&world_data_path)
+        .ok()
+        .and_then(|data| serde_json::from_str::<WorldData>(&data).ok())
+        .is_some();
+    if !world_data_ok {
+        // world.json only holds the display name, which the metadata keeps too.
+        let name = if metadata.name.is_empty() { world_name } else { &metadata.name };
+        let world_data = WorldData { name: name.to_string() };
+        fs::write(&world_data_path, serde_json::to_string(&world_data)?)
+            .with_context(|| format!("Failed to write {}", world_data_path))?;
+        report.restored_world_file = true;
+    }
+
+    let chunks_dir = format!("{}/chunks", save_dir);
+    fs::create_dir_all(&chunks_dir).with_context(|| format!("Failed to create {}", chunks_dir))?;
+
+    let mut has_player = false;
+    let mut chunk_files = Vec::new();
+    for entry in fs::read_dir(&chunks_dir)?.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        match parse_chunk_file_name(&file_name) {
+            Some(pos) => chunk_files.push((pos, entry.path())),
+            None => report.skipped_files.push(file_name),
+        }
+    }
+    chunk_files.sort_by_key(|(pos, _)| *pos);
+
+    for (pos, path) in &chunk_files {
+        let chunk = fs::read_to_string(path)
+            .ok()
+            .and_then(|data| Chunk::deserialize(&data, &tile_registry, &object_registry).ok())
+            .filter(|chunk| chunk.tiles.len() == CHUNK_SIZE * CHUNK_SIZE);
+        match chunk {
+            Some(chunk) => {
+                has_player |= !chunk.get_objects_by_type("player").is_empty();
+            }
+            None => {
+                let chunk = futures::executor::block_on(generate_chunk(*pos, &generator, &tile_registry, &object_registry, &biome_registry))?;
+                fs::write(path, chunk.serialize()).with_context(|| format!("Failed to write {}", path.display()))?;
+                report.regenerated_chunks.push(*pos);
+            }
+        }
+    }
+
+    if !has_player {
+        let spawn = metadata.spawn_point(&biome_registry, &content.manifest);
+        metadata.save(Path::new(&save_dir))?;
+        let (chunk_x, chunk_y) = spawn_chunk(spawn);
+        let spawn_path = Path::new(&chunks_dir).join(format!("chunk_{}_{}.json", chunk_x, chunk_y));
+        let spawn_chunk = fs::read_to_string(&spawn_path)
+            .ok()
+            .and_then(|data| Chunk::deserialize(&data, &tile_registry, &object_registry).ok());
+        let mut spawn_chunk = match spawn_chunk {
+            Some(chunk) => chunk,
+            None => {
+                report.regenerated_chunks.push((chunk_x, chunk_y));
+                futures::executor::block_on(generate_chunk((chunk_x, chunk_y), &generator, &tile_registry, &object_registry, &biome_registry))?
+            }
+        };
+        if let Some(mut player) = object_registry.create_object_by_id("player") {
+            player.set_pos(spawn_position(spawn));
+            spawn_chunk.objects.push(player);
+            report.restored_player = true;
+        }
+        fs::write(&spawn_path, spawn_chunk.serialize()).with_context(|| format!("Failed to write {}", spawn_path.display()))?;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,34 @@
+pub const MAX_SEED_LEN: usize = 64;
+
+/// Mixes all 64 bits of `x` into every output bit (the SplitMix64 finalizer).
+pub fn mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Turns the seed a player typed into a 64-bit world seed.
+///
+/// Whole numbers are used as they are, so numeric seeds keep meaning what they
+/// say. Any other text is hashed with FNV-1a over its UTF-8 bytes, which gives
+/// the same seed on every platform and in every version of the game.
+pub fn parse_seed(text: &str) -> u64 {
+    let text = text.trim();
+    if let Ok(n) = text.parse::<u64>() {
+        return n;
+    }
+    if let Ok(n) = text.parse::<i64>() {
+        return n as u64;
+    }
+    let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01B3);
+    }
+    mix64(hash)
+}
+
+pub fn random_seed() -> u64 {
+    ::rand::random()
+}
@@ -0,0 +1,141 @@
+use gaymwtf_core::{BiomeRegistry, CHUNK_SIZE, TILE_SIZE};
+use macroquad::prelude::*;
+
+use crate::content::Manifest;
+use crate::worldgen::WorldGenerator;
+
+/// Biomes a new player would rather start in, in no particular order.
+pub const PREFERRED_SPAWN_BIOMES: [&str; 4] = ["plains", "forest", "snow_plains", "snow_forest"];
+/// How far from the origin, in tiles, the search goes before giving up. The
+/// search runs on the UI thread when a world is made or repaired and checks
+/// up to `(2 * radius + 1)²` tiles. Each of the first 2000 mixed seeds has a
+/// preferred tile within 70.
+pub const MAX_SPAWN_RADIUS: i32 = 96;
+
+/// The chunk that contains tile `spawn`.
+pub fn spawn_chunk(spawn: (i32, i32)) -> (i32, i32) {
+    (spawn.0.div_euclid(CHUNK_SIZE as i32), spawn.1.div_euclid(CHUNK_SIZE as i32))
+}
+
+/// World position of a player standing on tile `spawn`.
+pub fn spawn_position(spawn: (i32, i32)) -> Vec2 {
+    vec2(spawn.0 as f32 * TILE_SIZE, spawn.1 as f32 * TILE_SIZE)
+}
+
+#[derive(PartialEq)]
+enum SpawnQuality {
+    Unusable,
+    Usable,
+    Preferred,
+}
+
+fn spawn_quality(
+    generator: &WorldGenerator,
+    biome_registry: &BiomeRegistry,
+    manifest: &Manifest,
+    x: i32,
+    y: i32,
+) -> SpawnQuality {
+    let (height, moisture, temperature) = generator.get_values(x, y);
+    let Some(biome) = biome_registry.find_biome(height, moisture, temperature) else {
+        return SpawnQuality::Unusable;
+    };
+    if !manifest.is_walkable(biome.get_ground_tile_type()) || !generator.objects_at(biome, x, y).is_empty() {
+        SpawnQuality::Unusable
+    } else if PREFERRED_SPAWN_BIOMES.contains(&biome.get_type_tag()) {
+        SpawnQuality::Preferred
+    } else {
+        SpawnQuality::Usable
+    }
+}
+
+/// Tiles on the square ring at distance `radius` from the origin, clockwise from the top-left.
+fn ring(radius: i32) -> impl Iterator<Item = (i32, i32)> {
+    let top = (-radius..=radius).map(move |x| (x, -radius));
+    let right = (-radius + 1..=radius).map(move |y| (radius, y));
+    let bottom = (-radius..radius).rev().map(move |x| (x, radius));
+    let left = (-radius + 1..radius).rev().map(move |y| (-radius, y));
+    top.chain(right).chain(bottom).chain(left)
+}
+
+/// Finds the tile closest to the origin where a player can safely appear.
+///
+/// Searches outward ring by ring for a walkable, object-free tile in one of the
+/// [`PREFERRED_SPAWN_BIOMES`]. If none is within [`MAX_SPAWN_RADIUS`], the
+/// closest walkable, object-free tile of any biome is used instead, and the
+/// origin only as a last resort. Walkable tiles are those of `manifest`.
+pub fn find_spawn(generator: &WorldGenerator, biome_registry: &BiomeRegistry, manifest: &Manifest) -> (i32, i32) {
+    let mut fallback = None;
+    for radius in 0..=MAX_SPAWN_RADIUS {
+        let tiles: Box<dyn Iterator<Item = (i32, i32)>> = if radius == 0 {
+            Box::new(std::iter::once((0, 0)))
+        } else {
+            Box::new(ring(radius))
+        };
+        for (x, y) in tiles {
+            match spawn_quality(generator, biome_registry, manifest, x, y) {
+                SpawnQuality::Preferred => return (x, y),
+                SpawnQuality::Usable if fallback.is_none() => fallback = Some((x, y)),
+                _ => {}
+            }
+        }
+    }
+    fallback.unwrap_or((0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::Content;
+    use crate::worldgen::seed::{mix64, parse_seed};
+
+    #[test]
+    fn ring_visits_each_tile_once() {
+        for radius in 1..6 {
+            let tiles: Vec<_> = ring(radius).collect();
+            let mut unique = tiles.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(tiles.len(), unique.len());
+            assert_eq!(tiles.len(), 8 * radius as usize);
+            assert!(tiles.iter().all(|&(x, y)| x.abs().max(y.abs()) == radius));
+        }
+    }
+
+    #[test]
+    fn spawn_is_never_on_water_or_an_object() {
+        let content = Content::base().unwrap();
+        let biome_registry = crate::init_biome_registry(&content).unwrap();
+        let seeds = (0..200u64)
+            .map(mix64)
+            .chain(["my cool world", "мой мир", "1", ""].map(parse_seed));
+        for seed in seeds {
+            let generator = WorldGenerator::new(seed);
+            let (x, y) = find_spawn(&generator, &biome_registry, &content.manifest);
+            let (height, moisture, temperature) = generator.get_values(x, y);
+            let biome = biome_registry
+                .find_biome(height, moisture, temperature)
+                .unwrap_or_else(|| panic!("seed {} spawns outside any biome at {:?}", seed, (x, y)));
+            assert_ne!(biome.get_ground_tile_type(), "water", "seed {} spawns on water at {:?}", seed, (x, y));
+            assert!(
+                generator.objects_at(biome, x, y).is_empty(),
+                "seed {} spawns on an object at {:?}",
+                seed,
+                (x, y)
+            );
+        }
+    }
+
+    #[test]
+    fn legacy_worlds_spawn_on_land() {
+        let content = Content::base().unwrap();
+        let biome_registry = crate::init_biome_registry(&content).unwrap();
+        for seed in 0..50u32 {
+            let generator = WorldGenerator::legacy(seed.wrapping_mul(2_654_435_761));
+            let (x, y) = find_spawn(&generator, &biome_registry, &content.manifest);
+            let (height, moisture, temperature) = generator.get_values(x, y);
+            let biome = biome_registry.find_biome(height, moisture, temperature);
+            assert!(biome.is_some_and(|b| content.manifest.is_walkable(b.get_ground_tile_type())));
+        }
+    }
+}